@@ -0,0 +1,523 @@
+//! Compatibility layer for the
+//! [Apigee JSONThreatProtection policy](https://cloud.google.com/apigee/docs/api-platform/reference/policies/json-threat-protection-policy),
+//! enabled by the `apigee` feature.
+//!
+//! The policy XML is parsed into a [`ValidatorConfig`] by [`Policy::from_xml`],
+//! and the errors are mapped to the Apigee faults by [`Fault::new`],
+//! so the existing policies could be migrated without rewriting them by hand.
+//!
+//! | Apigee element          | Constraint                                      |
+//! |-------------------------|-------------------------------------------------|
+//! | `ArrayElementCount`     | [`ValidatorConfig::max_array_entries`]            |
+//! | `ContainerDepth`        | [`ValidatorConfig::max_depth`]                    |
+//! | `ObjectEntryCount`      | [`ValidatorConfig::max_object_entries`]           |
+//! | `ObjectEntryNameLength` | [`ValidatorConfig::max_object_entry_name_length`] |
+//! | `StringValueLength`     | [`ValidatorConfig::max_string_length`]            |
+//!
+//! Same as Apigee, a missing element or a negative value means no limit.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use json_threat_protection::{self as jtp, apigee::{Fault, Policy}};
+//!
+//! let policy = Policy::from_xml(r#"
+//!     <JSONThreatProtection name="JSON-Threat-Protection-1">
+//!         <ArrayElementCount>2</ArrayElementCount>
+//!         <ContainerDepth>10</ContainerDepth>
+//!         <Source>request</Source>
+//!     </JSONThreatProtection>
+//! "#).unwrap();
+//!
+//! let err = jtp::from_str("[1, 2, 3]")
+//!     .with_config(policy.config.clone())
+//!     .validate()
+//!     .unwrap_err();
+//!
+//! let fault = Fault::new(policy.name.as_deref().unwrap_or_default(), &err);
+//! assert_eq!(fault.code, "steps.jsonthreatprotection.ExecutionFailed");
+//! assert_eq!(fault.name, "ExceededArrayElementCount");
+//! ```
+
+use crate::lexer::LexerError;
+use crate::read::ReadError;
+use crate::validator::ValidatorError;
+use crate::{ValidatorConfig, NO_LIMIT};
+use thiserror::Error;
+
+/// Fault code of the violations of the policy.
+pub const EXECUTION_FAILED: &str = "steps.jsonthreatprotection.ExecutionFailed";
+
+/// Fault code of the payloads that are not valid JSON.
+pub const INVALID_JSON: &str = "steps.jsonthreatprotection.InvalidJSON";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+/// Error occurred while parsing the Apigee policy XML
+pub enum ApigeeError {
+    /// The XML is malformed at the given byte offset
+    #[error("malformed XML (offset: {0})")]
+    MalformedXml(usize),
+
+    /// The root element is not `JSONThreatProtection`
+    #[error("unexpected root element (name: {0})")]
+    UnexpectedRootElement(String),
+
+    /// The value of an element is not an integer
+    #[error("invalid value (element: {element}, value: {value})")]
+    InvalidValue {
+        /// Name of the element
+        element: String,
+
+        /// Value of the element
+        value: String,
+    },
+}
+
+/// An Apigee JSONThreatProtection policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Policy {
+    /// The `name` attribute of the policy
+    pub name: Option<String>,
+
+    /// The `DisplayName` element of the policy
+    pub display_name: Option<String>,
+
+    /// The `Source` element of the policy, such as `request` or `response`
+    pub source: Option<String>,
+
+    /// The `enabled` attribute of the policy, `true` by default
+    pub enabled: bool,
+
+    /// The `continueOnError` attribute of the policy, `false` by default
+    pub continue_on_error: bool,
+
+    /// Constraints of the policy
+    pub config: ValidatorConfig,
+}
+
+impl Policy {
+    /// Parses the Apigee JSONThreatProtection policy XML.
+    ///
+    /// The elements that are not related to the constraints, such as `Properties`, are ignored.
+    ///
+    /// # Errors
+    ///
+    /// * [`ApigeeError`] - If the XML is malformed or the values are not integers.
+    pub fn from_xml(xml: &str) -> Result<Self, ApigeeError> {
+        let mut policy = Policy {
+            name: None,
+            display_name: None,
+            source: None,
+            enabled: true,
+            continue_on_error: false,
+            config: ValidatorConfig::default(),
+        };
+
+        let mut reader = XmlReader { xml, pos: 0 };
+        let mut elements: Vec<&str> = Vec::new();
+        let mut text = String::new();
+        let mut has_root = false;
+
+        while let Some(event) = reader.next()? {
+            match event {
+                XmlEvent::Start {
+                    name,
+                    attributes,
+                    empty,
+                } => {
+                    if elements.is_empty() {
+                        if has_root {
+                            return Err(ApigeeError::MalformedXml(reader.pos));
+                        }
+                        if name != "JSONThreatProtection" {
+                            return Err(ApigeeError::UnexpectedRootElement(name.to_string()));
+                        }
+                        has_root = true;
+                        policy.apply_attributes(attributes)?;
+                    }
+                    if elements.len() == 1 {
+                        text.clear();
+                    }
+
+                    match empty {
+                        true if elements.len() == 1 => policy.apply_element(name, "")?,
+                        true => (),
+                        false => elements.push(name),
+                    }
+                }
+                XmlEvent::End(name) => {
+                    if elements.pop() != Some(name) {
+                        return Err(ApigeeError::MalformedXml(reader.pos));
+                    }
+                    if elements.len() == 1 {
+                        policy.apply_element(name, text.trim())?;
+                    }
+                }
+                XmlEvent::Text(value) => match elements.len() {
+                    0 if !value.trim().is_empty() => {
+                        return Err(ApigeeError::MalformedXml(reader.pos))
+                    }
+                    2 => text.push_str(&value),
+                    _ => (),
+                },
+            }
+        }
+
+        if !has_root || !elements.is_empty() {
+            return Err(ApigeeError::MalformedXml(reader.pos));
+        }
+
+        Ok(policy)
+    }
+
+    fn apply_attributes(&mut self, attributes: Vec<(&str, String)>) -> Result<(), ApigeeError> {
+        for (name, value) in attributes {
+            match name {
+                "name" => self.name = Some(value),
+                "enabled" => self.enabled = parse_bool(name, &value)?,
+                "continueOnError" => self.continue_on_error = parse_bool(name, &value)?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_element(&mut self, name: &str, value: &str) -> Result<(), ApigeeError> {
+        match name {
+            "ArrayElementCount" => self.config.max_array_entries = parse_limit(name, value)?,
+            "ContainerDepth" => self.config.max_depth = parse_limit(name, value)?,
+            "ObjectEntryCount" => self.config.max_object_entries = parse_limit(name, value)?,
+            "ObjectEntryNameLength" => {
+                self.config.max_object_entry_name_length = parse_limit(name, value)?
+            }
+            "StringValueLength" => self.config.max_string_length = parse_limit(name, value)?,
+            "DisplayName" => self.display_name = Some(value.to_string()),
+            "Source" => self.source = Some(value.to_string()),
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+/// Parses the value of a limit, the empty or negative value means no limit.
+fn parse_limit(element: &str, value: &str) -> Result<usize, ApigeeError> {
+    let invalid = || ApigeeError::InvalidValue {
+        element: element.to_string(),
+        value: value.to_string(),
+    };
+
+    if value.is_empty() {
+        return Ok(NO_LIMIT);
+    }
+
+    match value.parse::<i64>() {
+        Ok(limit) if limit < 0 => Ok(NO_LIMIT),
+        Ok(limit) => usize::try_from(limit).map_err(|_| invalid()),
+        Err(_) => Err(invalid()),
+    }
+}
+
+fn parse_bool(attribute: &str, value: &str) -> Result<bool, ApigeeError> {
+    match value.trim() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(ApigeeError::InvalidValue {
+            element: attribute.to_string(),
+            value: value.to_string(),
+        }),
+    }
+}
+
+/// An Apigee fault that corresponds to a validation error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fault {
+    /// Fault code, such as `steps.jsonthreatprotection.ExecutionFailed`
+    pub code: &'static str,
+
+    /// Fault name, such as `ExceededArrayElementCount`
+    pub name: &'static str,
+
+    /// HTTP status code of the fault
+    pub status: u16,
+
+    /// Fault string, which is the `fault.faultstring` of the Apigee error response
+    pub message: String,
+}
+
+impl Fault {
+    /// Maps the validation error to the Apigee fault of the policy with the given name.
+    ///
+    /// The invalid JSON is mapped to the [`INVALID_JSON`] fault code and the `InvalidJSON` fault name,
+    /// and the constraints that are not supported by Apigee
+    /// are mapped to the `ExecutionFailed` fault name.
+    pub fn new(policy_name: &str, err: &ValidatorError) -> Self {
+        if is_invalid_json(err) {
+            return Fault {
+                code: INVALID_JSON,
+                name: "InvalidJSON",
+                status: 500,
+                message: format!(
+                    "JSONThreatProtection[{}]: Execution failed. reason: {}",
+                    policy_name, err
+                ),
+            };
+        }
+
+        let violation = match err {
+            ValidatorError::MaxArrayEntriesExceeded { position, .. } => Some((
+                "ExceededArrayElementCount",
+                "Exceeded array element count",
+                position,
+            )),
+            ValidatorError::MaxDepthExceeded { position, .. } => Some((
+                "ExceededContainerDepth",
+                "Exceeded container depth",
+                position,
+            )),
+            ValidatorError::MaxObjectEntriesExceeded { position, .. } => Some((
+                "ExceededObjectEntryCount",
+                "Exceeded object entry count",
+                position,
+            )),
+            ValidatorError::MaxObjectEntryNameLengthExceeded { position, .. } => Some((
+                "ExceededObjectEntryNameLength",
+                "Exceeded object entry name length",
+                position,
+            )),
+            ValidatorError::MaxStringLengthExceeded { position, .. } => Some((
+                "ExceededStringValueLength",
+                "Exceeded string value length",
+                position,
+            )),
+            _ => None,
+        };
+
+        let (name, reason) = match violation {
+            Some((name, reason, position)) => (
+                name,
+                format!(
+                    "JSONThreatProtection[{}]: {} at line {}",
+                    policy_name, reason, position.line
+                ),
+            ),
+            None => ("ExecutionFailed", err.to_string()),
+        };
+
+        Fault {
+            code: EXECUTION_FAILED,
+            name,
+            status: 500,
+            message: format!(
+                "JSONThreatProtection[{}]: Execution failed. reason: {}",
+                policy_name, reason
+            ),
+        }
+    }
+
+    /// Returns the Apigee error response body of the fault,
+    /// such as `{"fault":{"faultstring":"...","detail":{"errorcode":"..."}}}`.
+    pub fn to_json(&self) -> String {
+        let mut message = String::with_capacity(self.message.len());
+        for c in self.message.chars() {
+            match c {
+                '"' => message.push_str("\\\""),
+                '\\' => message.push_str("\\\\"),
+                '\n' => message.push_str("\\n"),
+                '\r' => message.push_str("\\r"),
+                '\t' => message.push_str("\\t"),
+                c if (c as u32) < 0x20 => message.push_str(&format!("\\u{:04x}", c as u32)),
+                c => message.push(c),
+            }
+        }
+
+        format!(
+            r#"{{"fault":{{"faultstring":"{}","detail":{{"errorcode":"{}"}}}}}}"#,
+            message, self.code
+        )
+    }
+}
+
+/// Whether the error is caused by the payload that is not valid JSON,
+/// rather than a constraint or a failure to read the payload.
+fn is_invalid_json(err: &ValidatorError) -> bool {
+    match err {
+        ValidatorError::InvalidJSON(_) | ValidatorError::TrailingData(_) => true,
+        ValidatorError::LexerError(LexerError::ReadError(
+            ReadError::IoError(..) | ReadError::BufferLimitExceeded(_) | ReadError::Bug { .. },
+        )) => false,
+        ValidatorError::LexerError(_) => true,
+        _ => false,
+    }
+}
+
+enum XmlEvent<'a> {
+    Start {
+        name: &'a str,
+        attributes: Vec<(&'a str, String)>,
+        empty: bool,
+    },
+    End(&'a str),
+    Text(String),
+}
+
+/// A minimal XML reader, which is just enough to read the flat policy XML.
+///
+/// It skips the XML declaration, processing instructions, comments and document type declarations,
+/// and does not support the internal subset of the document type declaration.
+struct XmlReader<'a> {
+    xml: &'a str,
+    pos: usize,
+}
+
+impl<'a> XmlReader<'a> {
+    fn next(&mut self) -> Result<Option<XmlEvent<'a>>, ApigeeError> {
+        loop {
+            let rest = &self.xml[self.pos..];
+            if rest.is_empty() {
+                return Ok(None);
+            }
+
+            if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata
+                    .find("]]>")
+                    .ok_or(ApigeeError::MalformedXml(self.pos))?;
+                self.pos += "<![CDATA[".len() + end + "]]>".len();
+                return Ok(Some(XmlEvent::Text(cdata[..end].to_string())));
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")?;
+            } else if let Some(end_tag) = rest.strip_prefix("</") {
+                let end = end_tag
+                    .find('>')
+                    .ok_or(ApigeeError::MalformedXml(self.pos))?;
+                self.pos += "</".len() + end + ">".len();
+                return Ok(Some(XmlEvent::End(end_tag[..end].trim_end())));
+            } else if rest.starts_with('<') {
+                return self.read_start_tag().map(Some);
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                let text = unescape(&rest[..end]).ok_or(ApigeeError::MalformedXml(self.pos))?;
+                self.pos += end;
+                return Ok(Some(XmlEvent::Text(text)));
+            }
+        }
+    }
+
+    fn skip_past(&mut self, pattern: &str) -> Result<(), ApigeeError> {
+        let end = self.xml[self.pos..]
+            .find(pattern)
+            .ok_or(ApigeeError::MalformedXml(self.pos))?;
+        self.pos += end + pattern.len();
+        Ok(())
+    }
+
+    fn read_start_tag(&mut self) -> Result<XmlEvent<'a>, ApigeeError> {
+        let xml = self.xml;
+        let malformed = |pos| ApigeeError::MalformedXml(pos);
+
+        // Skip the `<`.
+        self.pos += 1;
+        let name = self.read_name();
+        if name.is_empty() {
+            return Err(malformed(self.pos));
+        }
+
+        let mut attributes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let rest = &xml[self.pos..];
+
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(XmlEvent::Start {
+                    name,
+                    attributes,
+                    empty: true,
+                });
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                return Ok(XmlEvent::Start {
+                    name,
+                    attributes,
+                    empty: false,
+                });
+            }
+
+            let attribute = self.read_name();
+            if attribute.is_empty() {
+                return Err(malformed(self.pos));
+            }
+
+            self.skip_whitespace();
+            if !xml[self.pos..].starts_with('=') {
+                return Err(malformed(self.pos));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+
+            let rest = &xml[self.pos..];
+            let quote = match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(malformed(self.pos)),
+            };
+            let end = rest[1..].find(quote).ok_or(malformed(self.pos))?;
+            let value = unescape(&rest[1..1 + end]).ok_or(malformed(self.pos))?;
+            self.pos += 1 + end + 1;
+
+            attributes.push((attribute, value));
+        }
+    }
+
+    fn read_name(&mut self) -> &'a str {
+        let rest = &self.xml[self.pos..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+            .unwrap_or(rest.len());
+        self.pos += end;
+        &rest[..end]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.xml[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+}
+
+/// Replaces the predefined entities and the character references,
+/// returns `None` if there is an unknown entity.
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let end = rest.find(';')?;
+        let entity = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => entity.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        };
+        unescaped.push(c);
+    }
+
+    unescaped.push_str(rest);
+    Some(unescaped)
+}
@@ -0,0 +1,89 @@
+/// Characters allowed in object entry names.
+///
+/// Object entry names are not restricted by the JSON specification,
+/// but the names with control characters, whitespace or dots might break the downstream,
+/// such as the log pipelines or the document stores which treat dots as path separators.
+/// The characters are checked after the escape sequences are decoded,
+/// so `"a\u002eb"` is rejected the same as `"a.b"` if the dots are not allowed.
+///
+/// The default charset allows any character.
+///
+/// # Examples
+///
+/// ```rust
+/// use json_threat_protection::{self as jtp, KeyCharset};
+///
+/// let charset = KeyCharset {
+///     allow_control_characters: false,
+///     allow_dots: false,
+///     ..Default::default()
+/// };
+///
+/// assert!(jtp::from_str(r#"{"a b": 1}"#).with_key_charset(charset).validate().is_ok());
+/// assert!(jtp::from_str(r#"{"a.b": 1}"#).with_key_charset(charset).validate().is_err());
+///
+/// let charset = KeyCharset::ascii_identifier();
+/// assert!(jtp::from_str(r#"{"user_id1": 1}"#).with_key_charset(charset).validate().is_ok());
+/// assert!(jtp::from_str(r#"{"user-id": 1}"#).with_key_charset(charset).validate().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct KeyCharset {
+    /// Whether to allow the control characters, such as `\u0000` and `\n`
+    pub allow_control_characters: bool,
+
+    /// Whether to allow the Unicode whitespace characters, such as ` ` and `　`
+    pub allow_whitespace: bool,
+
+    /// Whether to allow `.`
+    pub allow_dots: bool,
+
+    /// Whether to only allow ASCII letters, digits and `_`
+    pub ascii_identifier_only: bool,
+}
+
+impl KeyCharset {
+    /// Only allows ASCII letters, digits and `_`.
+    pub fn ascii_identifier() -> Self {
+        KeyCharset {
+            allow_control_characters: false,
+            allow_whitespace: false,
+            allow_dots: false,
+            ascii_identifier_only: true,
+        }
+    }
+
+    /// Finds the first character of the decoded object entry name not allowed by this charset.
+    pub(crate) fn find_forbidden(&self, name: &str) -> Option<char> {
+        if *self == KeyCharset::default() {
+            return None;
+        }
+
+        name.chars().find(|&c| !self.allows(c))
+    }
+
+    fn allows(&self, c: char) -> bool {
+        if self.ascii_identifier_only && !(c.is_ascii_alphanumeric() || c == '_') {
+            return false;
+        }
+
+        (self.allow_control_characters || !c.is_control())
+            && (self.allow_whitespace || !c.is_whitespace())
+            && (self.allow_dots || c != '.')
+    }
+}
+
+impl Default for KeyCharset {
+    fn default() -> Self {
+        KeyCharset {
+            allow_control_characters: true,
+            allow_whitespace: true,
+            allow_dots: true,
+            ascii_identifier_only: false,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::config::ValidatorConfig;
+use crate::keys::{Fingerprinter, KeySpan};
+use crate::read::Position;
+use crate::validator::State;
+
+/// Snapshot of the progress of a [`crate::Validator`], taken by [`crate::Validator::checkpoint`].
+///
+/// A validator restored by [`crate::Validator::from_checkpoint`] continues the validation
+/// with a reader of the same payload positioned at [`Checkpoint::position`],
+/// such as a file seeked to the offset, or the rest of the chunks of an upload,
+/// so a large payload could be validated across process restarts or handed between threads.
+///
+/// The checkpoint holds the stacks of the states, the entry counters and the entry names,
+/// the fingerprints of the object entry names, and the constraints of the validator,
+/// but not the custom rules, which should be registered again by [`crate::Validator::with_rule`].
+///
+/// With the `serde` feature enabled, the checkpoint implements `Serialize` and `Deserialize`.
+///
+/// # Security
+///
+/// The checkpoint includes the secret of the fingerprints used to detect duplicate object entry names,
+/// whoever reads it could craft names that collide with each other,
+/// so it should be stored as privately as the payload itself.
+///
+/// # Examples
+///
+/// ```rust
+/// use json_threat_protection as jtp;
+///
+/// let data = br#"{"a": [1, 2, 3], "b": {"c": true}}"#;
+/// let mut validator = jtp::from_slice(data).with_max_depth(8);
+/// assert!(!validator.validate_with_steps(4).unwrap());
+///
+/// let checkpoint = validator.checkpoint();
+/// let offset = checkpoint.position().offset;
+/// jtp::Validator::from_checkpoint(jtp::read::SliceRead::new(&data[offset..]), checkpoint)
+///     .unwrap()
+///     .validate()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint {
+    /// Position right before the first byte that has not been validated
+    pub(crate) position: Position,
+
+    /// Stack of states
+    pub(crate) states: Vec<State>,
+
+    /// Stack of the number of entries in each array or object
+    pub(crate) entries: Vec<usize>,
+
+    /// Stack of the fingerprints, spans and kept spellings of the object entry names in each object
+    pub(crate) keys: Vec<Vec<(u64, KeySpan, Option<String>)>>,
+
+    /// Stack of the current entry name in each array or object
+    pub(crate) names: Vec<Option<String>>,
+
+    /// Current depth of the JSON
+    pub(crate) depth: usize,
+
+    /// Computes the fingerprints of the keys, including the secret
+    pub(crate) fingerprinter: Fingerprinter,
+
+    /// Constraints of the validator
+    pub(crate) config: ValidatorConfig,
+}
+
+impl Checkpoint {
+    /// Position right before the first byte that has not been validated,
+    /// where the reader of the restored validator should start.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Depth of the JSON structure at the checkpoint.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Check if the stacks are consistent with each other,
+    /// so a corrupted or forged checkpoint could not bypass the constraints, such as the depth.
+    pub(crate) fn check(&self) -> Result<(), &'static str> {
+        // The containers are followed by the state of the innermost one,
+        // unless the validation is finished.
+        let (top, containers) = match self.states.split_last() {
+            Some((top, containers)) => (Some(top), containers),
+            None => (None, &[][..]),
+        };
+        if containers
+            .iter()
+            .any(|state| !matches!(state, State::ProcessingArray | State::ProcessingObject))
+        {
+            return Err("states other than arrays and objects below the top");
+        }
+
+        let accepted = match (containers.last(), top) {
+            (_, None) => true,
+            (None, Some(top)) => *top == State::RequireElement,
+            (Some(State::ProcessingArray), Some(top)) => matches!(
+                top,
+                State::OptionalElement | State::RequireElement | State::OptionalComma
+            ),
+            (Some(_), Some(top)) => matches!(
+                top,
+                State::OptionalObjectKey
+                    | State::RequireObjectKey
+                    | State::RequireColon
+                    | State::RequireElement
+                    | State::OptionalComma
+            ),
+        };
+        if !accepted {
+            return Err("state at the top does not match the innermost container");
+        }
+
+        if self.depth != containers.len()
+            || self.entries.len() != containers.len()
+            || self.names.len() != containers.len()
+        {
+            return Err("depth, entries and names do not match the containers");
+        }
+
+        // The containers holding the inner ones have counted them as entries,
+        // and the objects have read their names.
+        let innermost = containers.len().saturating_sub(1);
+        let levels = containers.iter().zip(&self.entries).zip(&self.names);
+        for (level, ((state, &entries), name)) in levels.enumerate() {
+            let (min_entries, named) = match (state, level == innermost) {
+                (State::ProcessingArray, false) => (1, false),
+                (_, false) => (1, true),
+                (State::ProcessingArray, true) => (0, false),
+                (_, true) => (0, top != Some(&State::OptionalObjectKey)),
+            };
+            if entries < min_entries || name.is_some() != named {
+                return Err("entries or names do not match the containers");
+            }
+        }
+
+        let objects = match self.config.allow_duplicate_object_entry_name {
+            true => 0,
+            false => containers
+                .iter()
+                .filter(|state| **state == State::ProcessingObject)
+                .count(),
+        };
+        if self.keys.len() != objects {
+            return Err("fingerprints do not match the objects");
+        }
+
+        let offset = self.position.offset;
+        let beyond_position = self
+            .keys
+            .iter()
+            .flatten()
+            .any(|(_, span, _)| span.start > span.end || span.end > offset);
+        if beyond_position {
+            return Err("spans of the names beyond the position");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use crate::charset::KeyCharset;
+use crate::key_policy::KeyPolicy;
+use crate::length::LengthUnit;
+use crate::normalization::KeyNormalization;
+use crate::number::NumberPolicy;
+use crate::prototype::PrototypePollutionPolicy;
+use crate::read::SurrogatePolicy;
+use crate::unicode::UnicodeChecks;
+use crate::NO_LIMIT;
+
+/// Default maximum number of errors collected by [`crate::Validator::validate_all`].
+pub(crate) const DEFAULT_MAX_ERRORS: usize = 100;
+
+/// All the constraints of a [`crate::Validator`] in a single value.
+///
+/// The config could be built once, such as loading from a policy file,
+/// and shared across many validators by [`crate::Validator::with_config`].
+/// Wrapping it in an [`std::sync::Arc`] makes the sharing as cheap as a reference count increment.
+///
+/// The default config has no constraints and allows duplicate object entry names,
+/// which is the same as [`crate::Validator::new`].
+///
+/// With the `serde` feature enabled, the config implements `Serialize` and `Deserialize`,
+/// and the missing fields take their default values.
+/// The limits set to [`crate::NO_LIMIT`] are left out when serializing,
+/// so the config fits the formats whose integers are signed 64-bit, such as TOML.
+///
+/// # Examples
+///
+/// ```rust
+/// use json_threat_protection::{self as jtp, ValidatorConfig};
+/// use std::sync::Arc;
+///
+/// let config = Arc::new(ValidatorConfig {
+///     max_depth: 32,
+///     max_string_length: 1024,
+///     allow_duplicate_object_entry_name: false,
+///     ..Default::default()
+/// });
+///
+/// for payload in [r#"{"a": 1}"#, r#"[1, 2, 3]"#] {
+///     jtp::from_str(payload).with_config(config.clone()).validate().unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ValidatorConfig {
+    /// Maximum depth of the JSON structure
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_depth: usize,
+
+    /// Maximum size of the JSON payload in bytes
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_payload_bytes: usize,
+
+    /// Maximum length of strings
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_string_length: usize,
+
+    /// Maximum bytes of strings and object entry names in the source, excluding the quotes
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_string_raw_length: usize,
+
+    /// Maximum number of escape sequences in strings and object entry names
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_string_escapes: usize,
+
+    /// Unit of the length of strings and object entry names
+    pub length_unit: LengthUnit,
+
+    /// Checks of the code points in strings
+    pub unicode_checks: UnicodeChecks,
+
+    /// Policy of the lone surrogates in strings and object entry names
+    pub surrogate_policy: SurrogatePolicy,
+
+    /// Maximum length of numbers, including the sign, period, and exponent characters
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_number_length: usize,
+
+    /// Maximum number of digits before the period of numbers
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_number_integer_digits: usize,
+
+    /// Maximum number of digits after the period of numbers
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_number_fraction_digits: usize,
+
+    /// Maximum number of digits in the exponent of numbers
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_number_exponent_digits: usize,
+
+    /// Maximum absolute value of the exponent of numbers
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_exponent_magnitude: usize,
+
+    /// Policy of which numbers are accepted
+    pub number_policy: NumberPolicy,
+
+    /// Maximum number of entries in arrays
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_array_entries: usize,
+
+    /// Maximum number of entries in objects
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_object_entries: usize,
+
+    /// Maximum length of object entry names
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_object_entry_name_length: usize,
+
+    /// Characters allowed in object entry names
+    pub key_charset: KeyCharset,
+
+    /// Whether to allow duplicate object entry names
+    pub allow_duplicate_object_entry_name: bool,
+
+    /// Maximum bytes retained to detect duplicate object entry names
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_duplicate_detection_bytes: usize,
+
+    /// Normalization of object entry names before detecting duplicates,
+    /// the validation fails with [`crate::Error::FeatureNotEnabled`]
+    /// if it is not the default without the `unicode-normalization` feature
+    pub key_normalization: KeyNormalization,
+
+    /// Policy of which object entry names used for prototype pollution are rejected
+    pub prototype_pollution_policy: PrototypePollutionPolicy,
+
+    /// Policies of which object entry names are allowed or forbidden
+    pub key_policies: Vec<KeyPolicy>,
+
+    /// Maximum bytes allocated by the validator itself
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_no_limit"))]
+    pub max_validator_memory: usize,
+
+    /// Maximum number of errors collected by [`crate::Validator::validate_all`],
+    /// which is 100 by default, and `0` is the same as `1`,
+    /// [`crate::NO_LIMIT`] is serialized as [`i64::MAX`] since a missing field means the default
+    #[cfg_attr(feature = "serde", serde(with = "saturating"))]
+    pub max_errors: usize,
+}
+
+impl Default for ValidatorConfig {
+    fn default() -> Self {
+        ValidatorConfig {
+            max_depth: NO_LIMIT,
+            max_payload_bytes: NO_LIMIT,
+            max_string_length: NO_LIMIT,
+            max_string_raw_length: NO_LIMIT,
+            max_string_escapes: NO_LIMIT,
+            length_unit: LengthUnit::default(),
+            unicode_checks: UnicodeChecks::default(),
+            surrogate_policy: SurrogatePolicy::default(),
+            max_number_length: NO_LIMIT,
+            max_number_integer_digits: NO_LIMIT,
+            max_number_fraction_digits: NO_LIMIT,
+            max_number_exponent_digits: NO_LIMIT,
+            max_exponent_magnitude: NO_LIMIT,
+            number_policy: NumberPolicy::default(),
+            max_array_entries: NO_LIMIT,
+            max_object_entries: NO_LIMIT,
+            max_object_entry_name_length: NO_LIMIT,
+            key_charset: KeyCharset::default(),
+            allow_duplicate_object_entry_name: true,
+            max_duplicate_detection_bytes: NO_LIMIT,
+            key_normalization: KeyNormalization::default(),
+            prototype_pollution_policy: PrototypePollutionPolicy::default(),
+            key_policies: Vec::new(),
+            max_validator_memory: NO_LIMIT,
+            max_errors: DEFAULT_MAX_ERRORS,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn is_no_limit(limit: &usize) -> bool {
+    *limit == NO_LIMIT
+}
+
+/// Serializes [`NO_LIMIT`] as [`i64::MAX`] and reads it back,
+/// for the limits whose missing field is not [`NO_LIMIT`].
+#[cfg(feature = "serde")]
+mod saturating {
+    use crate::NO_LIMIT;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(limit: &usize, serializer: S) -> Result<S::Ok, S::Error> {
+        // The limits above `i64::MAX` are as good as no limit.
+        serializer.serialize_i64(i64::try_from(*limit).unwrap_or(i64::MAX))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+        let limit = u64::deserialize(deserializer)?;
+        if limit >= i64::MAX as u64 {
+            return Ok(NO_LIMIT);
+        }
+        usize::try_from(limit).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,131 @@
+//! Pull-based streaming API that yields the events of a JSON payload.
+//!
+//! The events are produced by the same state machine as [`crate::Validator`],
+//! so every event is syntactically valid up to its position
+//! and satisfies the constraints configured on the validator.
+
+use crate::read::{Position, Read};
+use crate::validator::{self, EventKind, ValidatorError};
+
+/// An event of the JSON payload.
+///
+/// The string of [`Event::Key`] and [`Event::String`] has been unescaped,
+/// and the string of [`Event::Number`] is the literal of the number as it is in the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event<'a> {
+    /// Start of an object, `{`
+    StartObject,
+
+    /// End of an object, `}`
+    EndObject,
+
+    /// Start of an array, `[`
+    StartArray,
+
+    /// End of an array, `]`
+    EndArray,
+
+    /// Name of an object entry
+    Key(&'a str),
+
+    /// String value
+    String(&'a str),
+
+    /// Number value
+    Number(&'a str),
+
+    /// `true` or `false`
+    Bool(bool),
+
+    /// `null`
+    Null,
+}
+
+/// A pull-based reader that yields the [`Event`]s of a JSON payload,
+/// created by [`crate::Validator::into_events`].
+///
+/// # Examples
+///
+/// ```rust
+/// use json_threat_protection::{self as jtp, event::Event};
+///
+/// fn collect_keys(data: &str) -> Result<Vec<String>, jtp::Error> {
+///     let mut events = jtp::from_str(data).with_max_depth(32).into_events();
+///     let mut keys = Vec::new();
+///
+///     while let Some((event, _position)) = events.next_event()? {
+///         if let Event::Key(key) = event {
+///             keys.push(key.to_string());
+///         }
+///     }
+///
+///     Ok(keys)
+/// }
+/// ```
+pub struct EventReader<R: Read> {
+    inner: validator::Validator<R>,
+
+    /// Whether the reader has returned the end of the payload or an error
+    finished: bool,
+}
+
+impl<R: Read> EventReader<R> {
+    pub(crate) fn new(inner: validator::Validator<R>) -> Self {
+        EventReader {
+            inner,
+            finished: false,
+        }
+    }
+
+    /// Reads the next event.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some((event, position)))` - The next event and the position right before its first byte.
+    /// * `Ok(None)` - If the entire JSON payload has been read, or an error has been returned before.
+    /// * `Err` - If the JSON payload is invalid or violates any constraints.
+    ///
+    /// # Errors
+    ///
+    /// * [`crate::Error`] - If the JSON payload is invalid or violates any constraints.
+    pub fn next_event(&mut self) -> Result<Option<(Event<'_>, Position)>, ValidatorError> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let kind = match self.inner.next_event() {
+            Ok(Some(kind)) => kind,
+            Ok(None) => {
+                self.finished = true;
+                return Ok(None);
+            }
+            Err(e) => {
+                self.finished = true;
+                return Err(e);
+            }
+        };
+
+        let event = match kind {
+            EventKind::StartObject => Event::StartObject,
+            EventKind::EndObject => Event::EndObject,
+            EventKind::StartArray => Event::StartArray,
+            EventKind::EndArray => Event::EndArray,
+            EventKind::Key => Event::Key(self.inner.token_str()),
+            EventKind::String => Event::String(self.inner.token_str()),
+            EventKind::Number => Event::Number(self.inner.token_str()),
+            EventKind::True => Event::Bool(true),
+            EventKind::False => Event::Bool(false),
+            EventKind::Null => Event::Null,
+        };
+
+        Ok(Some((event, self.inner.token_start())))
+    }
+
+    /// Returns the JSON Pointer (RFC 6901) of the value of the last event.
+    ///
+    /// For [`Event::Key`], it is the path of the value that follows the key,
+    /// and for the start and end of a container, it is the path of the container itself.
+    pub fn path(&self) -> String {
+        self.inner.path()
+    }
+}
@@ -0,0 +1,253 @@
+/// Pattern of object entry names used by [`KeyPolicy`].
+///
+/// The patterns are matched against the decoded object entry names.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum KeyPattern {
+    /// Matches the name exactly
+    Exact(String),
+
+    /// Matches the names starting with the prefix
+    Prefix(String),
+
+    /// Matches the names by a glob pattern,
+    /// where `*` matches any sequence of characters and `?` matches a single character,
+    /// the other characters are matched literally.
+    Glob(String),
+
+    /// Matches the names by a regular expression, which requires the `regex` feature.
+    ///
+    /// The regular expression is not anchored,
+    /// use `^` and `$` to match the whole name.
+    /// Without the feature, the validation fails with [`crate::Error::FeatureNotEnabled`].
+    Regex(KeyRegex),
+}
+
+impl KeyPattern {
+    /// Check if the decoded object entry name matches this pattern.
+    pub(crate) fn matches(&self, name: &str) -> bool {
+        match self {
+            KeyPattern::Exact(exact) => name == exact,
+            KeyPattern::Prefix(prefix) => name.starts_with(prefix.as_str()),
+            KeyPattern::Glob(glob) => glob_matches(glob, name),
+            KeyPattern::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+/// Regular expression of [`KeyPattern::Regex`].
+///
+/// Without the `regex` feature only the source of the expression is kept,
+/// so the configurations using it can still be deserialized,
+/// but are rejected with [`crate::Error::FeatureNotEnabled`] once validating.
+#[derive(Debug, Clone)]
+pub struct KeyRegex {
+    #[cfg(feature = "regex")]
+    regex: regex::Regex,
+
+    #[cfg(not(feature = "regex"))]
+    source: String,
+}
+
+impl KeyRegex {
+    /// Compiles the regular expression.
+    #[cfg(feature = "regex")]
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        regex::Regex::new(pattern).map(KeyRegex::from)
+    }
+
+    /// Source of the regular expression.
+    pub fn as_str(&self) -> &str {
+        #[cfg(feature = "regex")]
+        return self.regex.as_str();
+
+        #[cfg(not(feature = "regex"))]
+        return &self.source;
+    }
+
+    #[cfg(feature = "regex")]
+    fn is_match(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
+
+    /// The validator rejects the regular expressions before matching without the feature.
+    #[cfg(not(feature = "regex"))]
+    fn is_match(&self, _name: &str) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "regex")]
+impl From<regex::Regex> for KeyRegex {
+    fn from(regex: regex::Regex) -> Self {
+        KeyRegex { regex }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for KeyRegex {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for KeyRegex {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+
+        #[cfg(feature = "regex")]
+        return KeyRegex::new(&source).map_err(serde::de::Error::custom);
+
+        #[cfg(not(feature = "regex"))]
+        return Ok(KeyRegex { source });
+    }
+}
+
+impl PartialEq for KeyPattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (KeyPattern::Exact(a), KeyPattern::Exact(b)) => a == b,
+            (KeyPattern::Prefix(a), KeyPattern::Prefix(b)) => a == b,
+            (KeyPattern::Glob(a), KeyPattern::Glob(b)) => a == b,
+            (KeyPattern::Regex(a), KeyPattern::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for KeyPattern {}
+
+/// Whether the names matching the patterns are the only allowed ones or forbidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyPolicyMode {
+    /// Only the names matching any of the patterns are allowed
+    Allow,
+
+    /// The names matching any of the patterns are forbidden
+    Deny,
+}
+
+/// Policy of which object entry names are allowed or forbidden.
+///
+/// A policy applies to the object entry names at any depth by default,
+/// or only at the given depth by [`KeyPolicy::at_depth`],
+/// where the entries of the top-level object are at depth 1.
+/// The names violating any of the policies are rejected
+/// with [`crate::Error::ForbiddenObjectEntryName`].
+///
+/// # Examples
+///
+/// ```rust
+/// use json_threat_protection::{self as jtp, KeyPattern, KeyPolicy};
+///
+/// // Reject the MongoDB operators at any depth.
+/// let operators = KeyPolicy::deny(vec![KeyPattern::Prefix("$".to_string())]);
+///
+/// // Only allow the known fields at the top level.
+/// let fields = KeyPolicy::allow(vec![
+///     KeyPattern::Exact("name".to_string()),
+///     KeyPattern::Glob("filter_*".to_string()),
+/// ])
+/// .at_depth(1);
+///
+/// let validate = |payload: &str| {
+///     jtp::from_str(payload)
+///         .with_key_policy(operators.clone())
+///         .with_key_policy(fields.clone())
+///         .validate()
+/// };
+///
+/// assert!(validate(r#"{"name": "a", "filter_age": {"gt": 18}}"#).is_ok());
+/// assert!(validate(r#"{"name": {"$ne": null}}"#).is_err());
+/// assert!(validate(r#"{"_internal": true}"#).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyPolicy {
+    /// Whether the names matching the patterns are allowed or forbidden
+    pub mode: KeyPolicyMode,
+
+    /// Patterns of the object entry names
+    pub patterns: Vec<KeyPattern>,
+
+    /// Depth of the object entries the policy applies to, or all depths if `None`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub depth: Option<usize>,
+}
+
+impl KeyPolicy {
+    /// Creates a policy that only allows the names matching any of the patterns.
+    pub fn allow(patterns: Vec<KeyPattern>) -> Self {
+        KeyPolicy {
+            mode: KeyPolicyMode::Allow,
+            patterns,
+            depth: None,
+        }
+    }
+
+    /// Creates a policy that forbids the names matching any of the patterns.
+    pub fn deny(patterns: Vec<KeyPattern>) -> Self {
+        KeyPolicy {
+            mode: KeyPolicyMode::Deny,
+            patterns,
+            depth: None,
+        }
+    }
+
+    /// Applies the policy only to the object entries at the given depth.
+    pub fn at_depth(mut self, depth: usize) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Check if the decoded object entry name at the given depth is accepted by this policy.
+    pub(crate) fn accepts(&self, name: &str, depth: usize) -> bool {
+        if self.depth.is_some_and(|d| d != depth) {
+            return true;
+        }
+
+        let matched = self.patterns.iter().any(|pattern| pattern.matches(name));
+        match self.mode {
+            KeyPolicyMode::Allow => matched,
+            KeyPolicyMode::Deny => !matched,
+        }
+    }
+}
+
+/// Matches the name by a glob pattern, only backtracking to the last `*`,
+/// so the time is bounded by the product of the lengths even for hostile names.
+///
+/// The positions are byte offsets of the characters, so nothing is allocated per name.
+fn glob_matches(glob: &str, name: &str) -> bool {
+    let (mut g, mut n) = (0, 0);
+    // Position after the last `*` and the name position it is matched up to.
+    let mut star: Option<(usize, usize)> = None;
+
+    while let Some(c) = name[n..].chars().next() {
+        match glob[g..].chars().next() {
+            Some('*') => {
+                star = Some((g + 1, n));
+                g += 1;
+            }
+            Some(p) if p == '?' || p == c => {
+                g += p.len_utf8();
+                n += c.len_utf8();
+            }
+            _ => match star {
+                Some((after_star, matched)) => {
+                    // unwrap is safe since the `*` matched up to a character of the name.
+                    let skipped = name[matched..].chars().next().unwrap().len_utf8();
+                    g = after_star;
+                    n = matched + skipped;
+                    star = Some((after_star, n));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    glob[g..].chars().all(|c| c == '*')
+}
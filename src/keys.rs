@@ -0,0 +1,197 @@
+//! Memory-bounded detection of duplicate object entry names.
+//!
+//! Instead of keeping a copy of every object entry name,
+//! the validator keeps a keyed 64-bit fingerprint of each name
+//! and the span of the name in the source.
+//! The fingerprints are SipHash-1-3 keyed by a random secret,
+//! so the attacker could not craft names that collide with each other.
+//! Once two fingerprints collide, the validator decodes the earlier names from the source
+//! to confirm the duplicate if the reader supports [`crate::read::Read::consumed_slice`],
+//! otherwise the collision is treated as a duplicate,
+//! which is a false positive with a probability of roughly `n^2 / 2^65` for `n` names in an object.
+//!
+//! Once the names are normalized, the decoded spelling of each name is kept as well,
+//! so the earlier spelling of a duplicate is reported even if the reader could not look back,
+//! and the collisions are always confirmed.
+
+use crate::read::{Read, SliceRead, SurrogatePolicy, TokenLimits};
+use siphasher::sip::SipHasher13;
+use std::collections::hash_map::Entry;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+
+/// Bytes retained for each object entry name, the fingerprint and the span.
+const KEY_ENTRY_BYTES: usize = std::mem::size_of::<u64>() + std::mem::size_of::<KeySpan>();
+
+/// Bytes retained for each spelling besides the spelling itself, the offset and the pointer.
+const SPELLING_ENTRY_BYTES: usize = std::mem::size_of::<(usize, Box<str>)>();
+
+/// Byte offsets of an object entry name in the source, including the quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct KeySpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Fingerprints of the object entry names in an object.
+#[derive(Debug, Default)]
+pub(crate) struct KeySet {
+    spans: HashMap<u64, KeySpan, BuildHasherDefault<FingerprintHasher>>,
+
+    /// Names whose fingerprints collide with an earlier name but are not duplicates,
+    /// which are very rare, so they are kept aside instead of in the hash table.
+    collisions: Vec<(u64, KeySpan)>,
+
+    /// Decoded spellings of the names sorted by the start offsets of their spans,
+    /// which are only kept once the names are normalized.
+    spellings: Vec<(usize, Box<str>)>,
+
+    /// Bytes of the spellings themselves.
+    spelling_bytes: usize,
+}
+
+impl KeySet {
+    pub fn with_capacity(capacity: usize) -> Self {
+        KeySet {
+            spans: HashMap::with_capacity_and_hasher(capacity, Default::default()),
+            collisions: Vec::new(),
+            spellings: Vec::new(),
+            spelling_bytes: 0,
+        }
+    }
+
+    /// Bytes retained for a name with the optional spelling.
+    pub fn entry_bytes(spelling: Option<&str>) -> usize {
+        KEY_ENTRY_BYTES + spelling.map_or(0, |spelling| SPELLING_ENTRY_BYTES + spelling.len())
+    }
+
+    /// Bytes retained for all the names in the set, see [`KeySet::entry_bytes`].
+    pub fn retained_bytes(&self) -> usize {
+        self.len() * KEY_ENTRY_BYTES
+            + self.spellings.len() * SPELLING_ENTRY_BYTES
+            + self.spelling_bytes
+    }
+
+    /// Number of names in the set.
+    pub fn len(&self) -> usize {
+        self.spans.len() + self.collisions.len()
+    }
+
+    /// Spans of all the names with the fingerprint.
+    pub fn get(&self, fingerprint: u64) -> impl Iterator<Item = &KeySpan> {
+        self.spans.get(&fingerprint).into_iter().chain(
+            self.collisions
+                .iter()
+                .filter(move |(other, _)| *other == fingerprint)
+                .map(|(_, span)| span),
+        )
+    }
+
+    /// Decoded spelling of the name at the span if it is kept.
+    pub fn spelling(&self, span: &KeySpan) -> Option<&str> {
+        self.spellings
+            .binary_search_by_key(&span.start, |(start, _)| *start)
+            .ok()
+            .map(|i| &*self.spellings[i].1)
+    }
+
+    /// Adds a name, which is kept even if its fingerprint collides with an earlier name.
+    pub fn insert(&mut self, fingerprint: u64, span: KeySpan, spelling: Option<&str>) {
+        match self.spans.entry(fingerprint) {
+            Entry::Occupied(_) => self.collisions.push((fingerprint, span)),
+            Entry::Vacant(entry) => {
+                entry.insert(span);
+            }
+        }
+
+        if let Some(spelling) = spelling {
+            // the names are added in the order of the source except when restored from a checkpoint
+            let i = self
+                .spellings
+                .partition_point(|(start, _)| *start < span.start);
+            self.spellings.insert(i, (span.start, spelling.into()));
+            self.spelling_bytes += spelling.len();
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, KeySpan, Option<&str>)> + '_ {
+        self.spans
+            .iter()
+            .map(|(fingerprint, span)| (*fingerprint, *span))
+            .chain(self.collisions.iter().copied())
+            .map(|(fingerprint, span)| (fingerprint, span, self.spelling(&span)))
+    }
+}
+
+/// Computes the keyed fingerprints of object entry names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Fingerprinter {
+    secret: [u64; 2],
+}
+
+impl Fingerprinter {
+    /// Creates a fingerprinter with a random secret.
+    pub fn new() -> Self {
+        let state = RandomState::new();
+        Fingerprinter {
+            secret: [state.hash_one(0u64), state.hash_one(1u64)],
+        }
+    }
+
+    /// SipHash-1-3 of the name keyed by the secret.
+    pub fn fingerprint(&self, key: &[u8]) -> u64 {
+        let mut hasher = SipHasher13::new_with_keys(self.secret[0], self.secret[1]);
+        hasher.write(key);
+        hasher.finish()
+    }
+}
+
+/// Estimated bytes allocated by the hash table of the set, the colliding names and the spellings,
+/// where the table has a control byte for each bucket and keeps at least 1/8 of the buckets empty.
+pub(crate) fn table_bytes(set: &KeySet) -> usize {
+    let collisions = set.collisions.capacity() * KEY_ENTRY_BYTES
+        + set.spellings.capacity() * SPELLING_ENTRY_BYTES
+        + set.spelling_bytes;
+    let capacity = set.spans.capacity();
+    if capacity == 0 {
+        return collisions;
+    }
+
+    let buckets = capacity + capacity / 7 + 1;
+    buckets * (KEY_ENTRY_BYTES + 1) + 16 + collisions
+}
+
+/// Decodes an object entry name from the source, including the quotes.
+pub(crate) fn decode_key(raw: &[u8]) -> Option<String> {
+    let mut buf = Vec::with_capacity(raw.len());
+    // the name has been accepted, so it either has no lone surrogates,
+    // or they have been replaced by the policy
+    SliceRead::new(raw)
+        .next_likely_string(&mut buf, SurrogatePolicy::Replace, TokenLimits::default())
+        .ok()?;
+    String::from_utf8(buf).ok()
+}
+
+/// The fingerprints are already uniformly distributed,
+/// so they are used as the hash directly.
+#[derive(Default)]
+pub(crate) struct FingerprintHasher(u64);
+
+impl Hasher for FingerprintHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ byte as u64;
+        }
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = n;
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
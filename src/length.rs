@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// Unit of the length of strings and object entry names.
+///
+/// The limits often come from the downstream,
+/// such as the database columns sized in characters,
+/// or the Java and JavaScript clients that count the UTF-16 code units,
+/// so the length should be measured in the same unit.
+///
+/// The lengths are measured after the escape sequences are decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum LengthUnit {
+    /// UTF-8 bytes, this is the default unit.
+    #[default]
+    Bytes,
+
+    /// Unicode scalar values, which are the [`char`]s of Rust.
+    Chars,
+
+    /// UTF-16 code units, which are the lengths of Java and JavaScript strings.
+    Utf16CodeUnits,
+
+    /// Extended grapheme clusters, which are the user-perceived characters,
+    /// which requires the `unicode-segmentation` feature.
+    /// Without the feature, the validation fails with [`crate::Error::FeatureNotEnabled`].
+    Graphemes,
+}
+
+impl LengthUnit {
+    /// Check if the length of the decoded string measured in this unit exceeds the limit.
+    pub(crate) fn exceeds(&self, str: &str, limit: usize) -> bool {
+        // No unit takes more than the UTF-8 bytes for a string.
+        str.len() > limit && self.measure(str) > limit
+    }
+
+    fn measure(&self, str: &str) -> usize {
+        match self {
+            LengthUnit::Bytes => str.len(),
+            LengthUnit::Chars => str.chars().count(),
+            LengthUnit::Utf16CodeUnits => str.chars().map(char::len_utf16).sum(),
+            #[cfg(feature = "unicode-segmentation")]
+            LengthUnit::Graphemes => {
+                unicode_segmentation::UnicodeSegmentation::graphemes(str, true).count()
+            }
+            // The validator rejects the unit before measuring without the feature.
+            #[cfg(not(feature = "unicode-segmentation"))]
+            LengthUnit::Graphemes => str.chars().count(),
+        }
+    }
+}
+
+impl fmt::Display for LengthUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LengthUnit::Bytes => write!(f, "bytes"),
+            LengthUnit::Chars => write!(f, "chars"),
+            LengthUnit::Utf16CodeUnits => write!(f, "UTF-16 code units"),
+            LengthUnit::Graphemes => write!(f, "graphemes"),
+        }
+    }
+}
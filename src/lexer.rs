@@ -1,8 +1,10 @@
-use crate::read::{Position, Read};
+use crate::config::ValidatorConfig;
+use crate::read::{LoneSurrogate, Position, Read, StringSummary, SurrogatePolicy, TokenLimits};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 /// An error that occurred while lexing a JSON input.
+#[non_exhaustive]
 pub enum LexerError {
     /// Invalid UTF-8 sequence at the given position.
     #[error("invalid utf-8 sequence ({0}")]
@@ -37,6 +39,17 @@ pub struct Lexer<R: Read> {
     reader: R,
     peeked_str_buf: Vec<u8>,
     peeked: Option<Token>,
+
+    /// Position right before the first byte of the current token
+    start: Position,
+    peeked_start: Position,
+
+    /// Summary of the current string token
+    summary: StringSummary,
+    peeked_summary: StringSummary,
+
+    surrogate_policy: SurrogatePolicy,
+    limits: TokenLimits,
 }
 
 impl<R: Read> Lexer<R> {
@@ -45,6 +58,12 @@ impl<R: Read> Lexer<R> {
             reader,
             peeked_str_buf: Vec::with_capacity(64),
             peeked: None,
+            start: Position::default(),
+            peeked_start: Position::default(),
+            summary: StringSummary::default(),
+            peeked_summary: StringSummary::default(),
+            surrogate_policy: SurrogatePolicy::default(),
+            limits: TokenLimits::default(),
         }
     }
 
@@ -52,9 +71,109 @@ impl<R: Read> Lexer<R> {
         self.reader.position()
     }
 
+    /// Position right before the first byte of the next token,
+    /// which is the position of the peeked token if any.
+    pub fn next_token_position(&self) -> Position {
+        match self.peeked {
+            Some(_) => self.peeked_start,
+            None => self.position(),
+        }
+    }
+
+    /// Position right before the first byte of the token returned by the last [`Lexer::next`].
+    pub fn token_start(&self) -> Position {
+        self.start
+    }
+
+    /// Number of escape sequences in the string token returned by the last [`Lexer::next`].
+    pub fn token_escapes(&self) -> usize {
+        self.summary.escapes
+    }
+
+    /// Takes the lone surrogates kept by [`SurrogatePolicy::Report`]
+    /// in the string token returned by the last [`Lexer::next`].
+    pub fn take_token_lone_surrogates(&mut self) -> Vec<LoneSurrogate> {
+        std::mem::take(&mut self.summary.surrogates)
+    }
+
+    pub fn set_surrogate_policy(&mut self, policy: SurrogatePolicy) {
+        self.surrogate_policy = policy;
+    }
+
+    /// Limits the bytes of the next string or number appended to the buffer.
+    pub fn set_max_buffer_bytes(&mut self, limit: usize) {
+        self.limits.max_buffer_bytes = limit;
+    }
+
+    /// Limits the bytes of each string in the source, excluding the quotes.
+    pub fn set_max_raw_bytes(&mut self, limit: usize) {
+        self.limits.max_raw_bytes = limit;
+    }
+
+    /// Limits the number of escape sequences in each string.
+    pub fn set_max_escapes(&mut self, limit: usize) {
+        self.limits.max_escapes = limit;
+    }
+
+    /// Limits the length and the digits of each number.
+    pub fn set_number_limits(&mut self, config: &ValidatorConfig) {
+        self.limits.max_number_length = config.max_number_length;
+        self.limits.max_integer_digits = config.max_number_integer_digits;
+        self.limits.max_fraction_digits = config.max_number_fraction_digits;
+        self.limits.max_exponent_digits = config.max_number_exponent_digits;
+        self.limits.max_exponent_magnitude = config.max_exponent_magnitude;
+    }
+
+    /// Whether the literal of the next number is appended to the buffer.
+    pub fn set_buffer_numbers(&mut self, buffer_numbers: bool) {
+        self.limits.buffer_numbers = buffer_numbers;
+    }
+
+    pub fn set_max_payload_bytes(&mut self, limit: usize) {
+        self.reader.set_max_payload_bytes(limit);
+    }
+
+    /// Bytes allocated by the buffer of the peeked token, the lone surrogates of the tokens,
+    /// and the buffer of the reader.
+    pub fn buffer_capacity(&self) -> usize {
+        self.reader.buffer_capacity()
+            + self.peeked_str_buf.capacity()
+            + (self.summary.surrogates.capacity() + self.peeked_summary.surrogates.capacity())
+                * std::mem::size_of::<LoneSurrogate>()
+    }
+
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn skip_whitespace(&mut self) -> Result<(), LexerError> {
+        Ok(self.reader.skip_whitespace()?)
+    }
+
+    pub fn mark(&mut self) {
+        self.reader.mark();
+    }
+
+    pub fn rewind(&mut self) {
+        self.reader.rewind();
+    }
+
+    pub fn consumed_slice(&self, start: usize, end: usize) -> Option<&[u8]> {
+        self.reader.consumed_slice(start, end)
+    }
+
     pub fn peek(&mut self, str_buf: &mut Vec<u8>) -> Result<Option<Token>, LexerError> {
         if self.peeked.is_none() {
+            let start = self.start;
+            let summary = std::mem::take(&mut self.summary);
             self.peeked = self.next(str_buf)?;
+            self.peeked_start = self.start;
+            self.peeked_summary = std::mem::replace(&mut self.summary, summary);
+            self.start = start;
             self.peeked_str_buf.clear();
             self.peeked_str_buf.extend_from_slice(str_buf);
         }
@@ -65,8 +184,10 @@ impl<R: Read> Lexer<R> {
         if self.peeked.is_some() {
             let peeked = self.peeked;
             self.peeked = None;
+            self.start = self.peeked_start;
+            self.summary = std::mem::take(&mut self.peeked_summary);
 
-            if matches!(peeked, Some(Token::String)) {
+            if matches!(peeked, Some(Token::String | Token::Number)) {
                 str_buf.clear();
                 str_buf.extend_from_slice(&self.peeked_str_buf);
             }
@@ -75,6 +196,7 @@ impl<R: Read> Lexer<R> {
         }
 
         self.reader.skip_whitespace()?;
+        self.start = self.reader.position();
         let peek = self.reader.peek()?;
         if peek.is_none() {
             return Ok(None);
@@ -116,14 +238,16 @@ impl<R: Read> Lexer<R> {
             b't' => Ok(Some(self.parse_true()?)),
             b'f' => Ok(Some(self.parse_false()?)),
             b'n' => Ok(Some(self.parse_null()?)),
-            b'-' | b'+' | b'0'..=b'9' => Ok(Some(self.parse_number()?)),
+            b'-' | b'+' | b'0'..=b'9' => Ok(Some(self.parse_number(str_buf)?)),
             _ => Err(LexerError::UnexpectedByte(self.position())),
         }
     }
 
     fn parse_string(&mut self, str_buf: &mut Vec<u8>) -> Result<Token, LexerError> {
         str_buf.clear();
-        self.reader.next_likely_string(str_buf)?;
+        self.summary =
+            self.reader
+                .next_likely_string(str_buf, self.surrogate_policy, self.limits)?;
 
         let str = std::str::from_utf8(str_buf);
         if str.is_err() {
@@ -133,8 +257,9 @@ impl<R: Read> Lexer<R> {
         Ok(Token::String)
     }
 
-    fn parse_number(&mut self) -> Result<Token, LexerError> {
-        match self.reader.next_number() {
+    fn parse_number(&mut self, str_buf: &mut Vec<u8>) -> Result<Token, LexerError> {
+        str_buf.clear();
+        match self.reader.next_number(str_buf, self.limits) {
             Ok(_) => Ok(Token::Number),
             Err(e) => Err(e.into()),
         }
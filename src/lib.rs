@@ -2,11 +2,24 @@
 //!
 //! This crate provides functionality to validate JSON payloads against a set of constraints.
 //! * Maximum depth of the JSON structure.
-//! * Maximum length of strings.
+//! * Maximum size of the payload in bytes.
+//! * Maximum length of strings, measured in bytes, characters, UTF-16 code units or graphemes,
+//!   see [`LengthUnit`].
+//! * Maximum length of strings in the source, and the number of their escape sequences.
+//! * Whether to reject dangerous code points in strings, see [`UnicodeChecks`].
+//! * Whether to reject or replace lone surrogates in strings, see [`SurrogatePolicy`].
+//! * Maximum length of numbers, and the number of their integer, fraction and exponent digits.
+//! * Maximum magnitude of the exponent of numbers.
+//! * Whether numbers must fit in Rust's native number types, see [`NumberPolicy`].
 //! * Maximum number of entries in arrays.
 //! * Maximum number of entries in objects.
 //! * Maximum length of object entry names.
-//! * Whether to allow duplicate object entry names.
+//! * Characters allowed in object entry names, see [`KeyCharset`].
+//! * Whether to allow duplicate object entry names, and the memory retained to detect them.
+//! * Whether to reject object entry names used for prototype pollution, see [`PrototypePollutionPolicy`].
+//! * Which object entry names are allowed or forbidden, see [`KeyPolicy`].
+//! * Maximum memory allocated by the validator itself.
+//! * Custom rules implemented by the [`rule::Rule`] trait.
 //!
 //! This crate is designed to process untrusted JSON payloads,
 //! such as it does not use recursion to validate the JSON structure.
@@ -20,10 +33,18 @@
 //!     jtp::from_slice(data).with_max_depth(depth).validate()
 //! }
 //!
+//! fn reject_too_large_payloads(data: &[u8], max_payload_bytes: usize) -> Result<(), jtp::Error> {
+//!     jtp::from_slice(data).with_max_payload_bytes(max_payload_bytes).validate()
+//! }
+//!
 //! fn reject_too_long_strings(data: &[u8], max_string_length: usize) -> Result<(), jtp::Error> {
 //!     jtp::from_slice(data).with_max_string_length(max_string_length).validate()
 //! }
 //!
+//! fn reject_too_long_numbers(data: &[u8], max_number_length: usize) -> Result<(), jtp::Error> {
+//!     jtp::from_slice(data).with_max_number_length(max_number_length).validate()
+//! }
+//!
 //! fn reject_too_many_array_entries(data: &[u8], max_array_entries: usize) -> Result<(), jtp::Error> {
 //!     jtp::from_slice(data).with_max_array_entries(max_array_entries).validate()
 //! }
@@ -48,6 +69,42 @@
 //!
 //! You could set the limit to [`NO_LIMIT`] to disable a specific constraint.
 //!
+//! # Configuration
+//!
+//! All the constraints could also be set at once by a [`ValidatorConfig`],
+//! which is useful to load the policies from files and share them across many validators.
+//!
+//! ```rust
+//! use json_threat_protection::{self as jtp, ValidatorConfig};
+//! use std::sync::Arc;
+//!
+//! fn validate_with_policy(data: &[u8], config: &Arc<ValidatorConfig>) -> Result<(), jtp::Error> {
+//!     jtp::from_slice(data).with_config(config.clone()).validate()
+//! }
+//! ```
+//!
+//! Enable the `serde` feature to serialize and deserialize the [`ValidatorConfig`],
+//! and the `apigee` feature to migrate the Apigee JSONThreatProtection policies, see the `apigee` module.
+//!
+//! # Collecting all errors
+//!
+//! By default, the validation stops at the first error,
+//! use [`Validator::validate_all`] to collect every constraint violation in the JSON payload.
+//!
+//! ```rust
+//! use json_threat_protection as jtp;
+//!
+//! fn report_all_violations(data: &[u8]) -> Vec<jtp::Error> {
+//!     jtp::from_slice(data)
+//!         .with_max_string_length(16)
+//!         .with_max_array_entries(8)
+//!         .with_max_errors(100)
+//!         .validate_all()
+//!         .err()
+//!         .unwrap_or_default()
+//! }
+//! ```
+//!
 //! # Incremental validation
 //!
 //! The `Validator` struct is designed to be used incrementally,
@@ -76,6 +133,71 @@
 //! the typical use case is used to build FFI bindings to other software
 //! that needs to validate JSON payloads in a non-blocking way to avoid blocking the thread.
 //!
+//! If the JSON payload arrives in chunks, such as the request body delivered by a proxy,
+//! use [`from_chunks`] to push each chunk by [`Validator::feed`] as soon as it arrives,
+//! and [`Validator::finish`] once the payload ends.
+//!
+//! ```rust
+//! use json_threat_protection as jtp;
+//!
+//! fn validate_chunks(chunks: &[&[u8]]) -> Result<(), jtp::Error> {
+//!     let mut validator = jtp::from_chunks().with_max_payload_bytes(1024 * 1024);
+//!     for chunk in chunks {
+//!         validator.feed(chunk)?;
+//!     }
+//!     validator.finish()
+//! }
+//! ```
+//!
+//! With the `tokio` or `futures-io` feature, `from_tokio_reader` or `from_async_reader`
+//! validates an asynchronous source by `Validator::validate_async`,
+//! which reads and validates the chunks without blocking the executor thread.
+//!
+//! A non-blocking [`std::io::Read`], such as a socket in non-blocking mode,
+//! could be validated by [`Validator::validate_nonblocking`],
+//! which returns `Ok(false)` once the reader returns [`std::io::ErrorKind::WouldBlock`].
+//!
+//! The progress could be saved by [`Validator::checkpoint`] between the calls,
+//! and restored by [`Validator::from_checkpoint`] with a reader positioned at [`Checkpoint::position`],
+//! so a huge payload could be validated across process restarts or handed between threads.
+//! With the `serde` feature, the [`Checkpoint`] implements `Serialize` and `Deserialize`,
+//! and a deserialized checkpoint whose stacks are not consistent is rejected by [`Error::InvalidCheckpoint`].
+//!
+//! # Custom rules
+//!
+//! Domain specific rules, such as rejecting a key named `$where`,
+//! could be enforced by implementing the [`rule::Rule`] trait
+//! and registering it with [`Validator::with_rule`].
+//! The callbacks receive the path and the depth of each value,
+//! and returning an `Err` aborts the validation with [`Error::Custom`].
+//!
+//! # Streaming events
+//!
+//! Use [`Validator::into_events`] to read the JSON payload as a stream of [`event::Event`]s,
+//! such as building custom policies or extracting values without a second parser.
+//!
+//! ```rust
+//! use json_threat_protection::{self as jtp, event::Event};
+//!
+//! fn find_string(data: &[u8], pointer: &str) -> Result<Option<String>, jtp::Error> {
+//!     let mut events = jtp::from_slice(data).with_max_depth(32).into_events();
+//!
+//!     while let Some((event, _position)) = events.next_event()? {
+//!         // The event borrows the reader, so take the value before asking for the path.
+//!         let value = match event {
+//!             Event::String(value) => value.to_string(),
+//!             _ => continue,
+//!         };
+//!
+//!         if events.path() == pointer {
+//!             return Ok(Some(value));
+//!         }
+//!     }
+//!
+//!     Ok(None)
+//! }
+//! ```
+//!
 //! # Error handling
 //!
 //! This crate has limited place where might panic, most of errors are returned as `Err`.
@@ -86,6 +208,9 @@
 //! it always contains the position where the error occurred, such as line, column, and offset,
 //! the `offset` is the byte offset from the beginning of the JSON payload.
 //!
+//! The constraint violations also contain the [JSON Pointer](https://datatracker.ietf.org/doc/html/rfc6901)
+//! of the offending value, such as `/users/3/name`, see [`Error::path`].
+//!
 //! # Special behavior compared to [serde_json](https://crates.io/crates/serde_json)
 //!
 //! This crate do it best to keep consistent with `serde_json`'s behavior
@@ -94,11 +219,12 @@
 //! and compare the validation results.
 //!
 //! However, there are some differences between this crate and `serde_json` so far:
-//! * This crate allow any precision of numbers,
+//! * This crate allow any precision of numbers by default,
 //!   even if it cannot be represented in Rust's native number types ([`i64`], [`u64`], [`i128`], [`u128`], [`f64`], [`f128`]).
 //!   The [serde_json](https://crates.io/crates/serde_json)
 //!   without [arbitrary_precision](https://github.com/serde-rs/json/blob/3f1c6de4af28b1f6c5100da323f2bffaf7c2083f/Cargo.toml#L69-L75)
 //!   feature enabled will return an error for such numbers.
+//!   You could use [`Validator::with_number_policy`] to reject such numbers.
 //!
 //! # Performance
 //!
@@ -120,16 +246,43 @@
 //! The initial seed corpus is from [nlohmann/json_test_data](https://github.com/nlohmann/json_test_data/),
 //! and extra corpus follows the [nlohmann/json/blob/develop/tests/fuzzing](https://github.com/nlohmann/json/blob/develop/tests/fuzzing.md).
 //!
+#[cfg(feature = "apigee")]
+pub mod apigee;
+mod charset;
+mod checkpoint;
+mod config;
+pub mod event;
+mod key_policy;
+mod keys;
+mod length;
 mod lexer;
+mod normalization;
+mod number;
+mod prototype;
 pub mod read;
+pub mod rule;
+mod unicode;
 mod validator;
 
-use read::{IoRead, Read, SliceRead, StrRead};
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use read::{AsyncIoRead, AsyncSource};
+use read::{FeedRead, IoRead, Read, SliceRead, StrRead};
+use std::sync::Arc;
 
 /// Represents no limit for a specific constraint.
 pub const NO_LIMIT: usize = usize::MAX;
+pub use charset::KeyCharset;
+pub use checkpoint::Checkpoint;
+pub use config::ValidatorConfig;
+pub use key_policy::{KeyPattern, KeyPolicy, KeyPolicyMode, KeyRegex};
+pub use length::LengthUnit;
 pub use lexer::LexerError;
-pub use read::ReadError;
+pub use normalization::{KeyNormalization, NormalizationForm};
+pub use number::NumberPolicy;
+pub use prototype::PrototypePollutionPolicy;
+pub use read::{ReadError, SurrogatePolicy};
+pub use unicode::UnicodeChecks;
+pub use validator::Progress;
 pub use validator::ValidatorError as Error;
 
 /// The JSON validator.
@@ -142,12 +295,37 @@ impl<R: Read> Validator<R> {
     /// You could prefer to use the [`from_slice`], [`from_str`], or [`from_reader`] functions
     pub fn new(read: R) -> Self {
         Validator {
-            inner: validator::Validator::new(
-                read, NO_LIMIT, NO_LIMIT, NO_LIMIT, NO_LIMIT, NO_LIMIT, true,
-            ),
+            inner: validator::Validator::new(read, Arc::default()),
         }
     }
 
+    /// Restores a `Validator` instance from the [`Checkpoint`] taken by [`Validator::checkpoint`],
+    /// the reader should start at [`Checkpoint::position`] of the same payload.
+    ///
+    /// The constraints are restored from the checkpoint as well,
+    /// but the custom rules should be registered again.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidCheckpoint`] - If the stacks of the checkpoint are not consistent,
+    ///   such as a corrupted or forged checkpoint.
+    pub fn from_checkpoint(read: R, checkpoint: Checkpoint) -> Result<Self, Error> {
+        Ok(Validator {
+            inner: validator::Validator::from_checkpoint(read, checkpoint)?,
+        })
+    }
+
+    /// Replaces all the constraints with the given config,
+    /// the constraints set before this call are discarded.
+    ///
+    /// Passing an [`Arc<ValidatorConfig>`] shares the config without copying it,
+    /// and it is only copied once a `with_*` method is called after this call.
+    pub fn with_config(mut self, config: impl Into<Arc<ValidatorConfig>>) -> Self {
+        let inner = self.inner.with_config(config.into());
+        self.inner = inner;
+        self
+    }
+
     /// Sets the maximum depth of the JSON structure.
     pub fn with_max_depth(mut self, max_depth: usize) -> Self {
         let inner = self.inner.with_max_depth(max_depth);
@@ -155,6 +333,16 @@ impl<R: Read> Validator<R> {
         self
     }
 
+    /// Sets the maximum size of the JSON payload in bytes.
+    ///
+    /// The validation aborts as soon as the reader has consumed more bytes than the limit,
+    /// so the validator never reads the entire payload if it is too large.
+    pub fn with_max_payload_bytes(mut self, max_payload_bytes: usize) -> Self {
+        let inner = self.inner.with_max_payload_bytes(max_payload_bytes);
+        self.inner = inner;
+        self
+    }
+
     /// Sets the maximum length of strings.
     pub fn with_max_string_length(mut self, max_string_length: usize) -> Self {
         let inner = self.inner.with_max_string_length(max_string_length);
@@ -162,7 +350,164 @@ impl<R: Read> Validator<R> {
         self
     }
 
+    /// Sets the maximum bytes of strings and object entry names in the source,
+    /// excluding the quotes.
+    ///
+    /// Unlike [`Validator::with_max_string_length`] which limits the decoded strings,
+    /// this limit counts the escape sequences as they are,
+    /// so `"\u0041"` takes 6 bytes instead of 1.
+    ///
+    /// The limit is checked while the string is read,
+    /// so an oversized string is rejected before its end is reached,
+    /// and the path of an oversized object entry name points at the object.
+    pub fn with_max_string_raw_length(mut self, max_string_raw_length: usize) -> Self {
+        let inner = self.inner.with_max_string_raw_length(max_string_raw_length);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the maximum number of escape sequences in each string and object entry name,
+    /// where a surrogate pair such as `"\ud83d\ude00"` counts as two escape sequences.
+    ///
+    /// Like [`Validator::with_max_string_raw_length`], the limit is checked while the string is read.
+    pub fn with_max_string_escapes(mut self, max_string_escapes: usize) -> Self {
+        let inner = self.inner.with_max_string_escapes(max_string_escapes);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the policy of the lone surrogates in strings and object entry names,
+    /// such as `"\uD800"`, see [`SurrogatePolicy`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_threat_protection::{self as jtp, ReadError, SurrogatePolicy};
+    /// use json_threat_protection::read::{LoneSurrogate, SurrogateKind};
+    /// use json_threat_protection::rule::{Context, Rule, RuleError};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let payload = r#"["\uD800"]"#;
+    ///
+    /// // rejected by default
+    /// let err = jtp::from_str(payload).validate().unwrap_err();
+    /// assert!(matches!(
+    ///     err,
+    ///     jtp::Error::LexerError(jtp::LexerError::ReadError(ReadError::LoneHighSurrogate(_)))
+    /// ));
+    ///
+    /// jtp::from_str(payload)
+    ///     .with_surrogate_policy(SurrogatePolicy::Replace)
+    ///     .validate()
+    ///     .unwrap();
+    ///
+    /// // accepted, and each lone surrogate is passed to the rules
+    /// struct Surrogates(Arc<Mutex<Vec<LoneSurrogate>>>);
+    ///
+    /// impl Rule for Surrogates {
+    ///     fn on_lone_surrogate(&mut self, _ctx: &Context<'_>, s: &LoneSurrogate) -> Result<(), RuleError> {
+    ///         self.0.lock().unwrap().push(*s);
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let found = Arc::new(Mutex::new(Vec::new()));
+    /// jtp::from_str(payload)
+    ///     .with_surrogate_policy(SurrogatePolicy::Report)
+    ///     .with_rule(Surrogates(found.clone()))
+    ///     .validate()
+    ///     .unwrap();
+    /// let found = found.lock().unwrap();
+    /// assert_eq!(found[0].kind, SurrogateKind::High);
+    /// assert_eq!(found[0].position.offset, 2);
+    /// ```
+    pub fn with_surrogate_policy(mut self, surrogate_policy: SurrogatePolicy) -> Self {
+        let inner = self.inner.with_surrogate_policy(surrogate_policy);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the unit of the length of strings and object entry names,
+    /// see [`LengthUnit`] for details.
+    ///
+    /// The unit applies to both [`Validator::with_max_string_length`]
+    /// and [`Validator::with_max_object_entry_name_length`].
+    pub fn with_length_unit(mut self, length_unit: LengthUnit) -> Self {
+        let inner = self.inner.with_length_unit(length_unit);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the checks of the code points in string values, see [`UnicodeChecks`] for details.
+    pub fn with_unicode_checks(mut self, unicode_checks: UnicodeChecks) -> Self {
+        let inner = self.inner.with_unicode_checks(unicode_checks);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the maximum length of numbers,
+    /// including the sign, period, and exponent characters.
+    ///
+    /// The length and the digit limits of numbers are checked while the number is read,
+    /// so an oversized number is rejected at the first character over the limit,
+    /// without buffering the whole literal.
+    pub fn with_max_number_length(mut self, max_number_length: usize) -> Self {
+        let inner = self.inner.with_max_number_length(max_number_length);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the maximum number of digits before the period of numbers.
+    pub fn with_max_number_integer_digits(mut self, max_number_integer_digits: usize) -> Self {
+        let inner = self
+            .inner
+            .with_max_number_integer_digits(max_number_integer_digits);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the maximum number of digits after the period of numbers.
+    pub fn with_max_number_fraction_digits(mut self, max_number_fraction_digits: usize) -> Self {
+        let inner = self
+            .inner
+            .with_max_number_fraction_digits(max_number_fraction_digits);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the maximum number of digits in the exponent of numbers.
+    pub fn with_max_number_exponent_digits(mut self, max_number_exponent_digits: usize) -> Self {
+        let inner = self
+            .inner
+            .with_max_number_exponent_digits(max_number_exponent_digits);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the maximum absolute value of the exponent of numbers,
+    /// such as `1e999999999` would be rejected if the limit is less than `999999999`.
+    ///
+    /// The literal exponent is checked regardless of the integer and fraction digits,
+    /// so `0.001e3` has an exponent magnitude of `3`.
+    pub fn with_max_exponent_magnitude(mut self, max_exponent_magnitude: usize) -> Self {
+        let inner = self
+            .inner
+            .with_max_exponent_magnitude(max_exponent_magnitude);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the policy of which numbers are accepted, see [`NumberPolicy`] for details.
+    pub fn with_number_policy(mut self, number_policy: NumberPolicy) -> Self {
+        let inner = self.inner.with_number_policy(number_policy);
+        self.inner = inner;
+        self
+    }
+
     /// Sets the maximum number of entries in arrays.
+    ///
+    /// Nested arrays and objects are entries of their parent like any other value,
+    /// and an entry is counted before its value is checked.
     pub fn with_max_array_entries(mut self, max_array_length: usize) -> Self {
         let inner = self.inner.with_max_array_entries(max_array_length);
         self.inner = inner;
@@ -170,6 +515,9 @@ impl<R: Read> Validator<R> {
     }
 
     /// Sets the maximum number of entries in objects.
+    ///
+    /// Nested arrays and objects are entries of their parent like any other value,
+    /// and an entry is counted before its value is checked.
     pub fn with_max_object_entries(mut self, max_object_length: usize) -> Self {
         let inner = self.inner.with_max_object_entries(max_object_length);
         self.inner = inner;
@@ -188,6 +536,13 @@ impl<R: Read> Validator<R> {
         self
     }
 
+    /// Sets the characters allowed in object entry names, see [`KeyCharset`] for details.
+    pub fn with_key_charset(mut self, key_charset: KeyCharset) -> Self {
+        let inner = self.inner.with_key_charset(key_charset);
+        self.inner = inner;
+        self
+    }
+
     /// Allows duplicate object entry names.
     pub fn allow_duplicate_object_entry_name(mut self) -> Self {
         let inner = self.inner.allow_duplicate_object_entry_name();
@@ -195,13 +550,80 @@ impl<R: Read> Validator<R> {
         self
     }
 
+    /// Sets the maximum bytes retained to detect duplicate object entry names.
+    ///
+    /// The validator keeps a keyed 64-bit fingerprint and the source span of each object entry name
+    /// instead of a copy of the name, which takes a fixed number of bytes per name
+    /// no matter how long the name is, and this limit caps the total of them,
+    /// including the spellings kept once the names are normalized.
+    /// The allocation of the hash tables might be up to about twice of that.
+    ///
+    /// The limit only takes effect once duplicate object entry names are disallowed.
+    pub fn with_max_duplicate_detection_bytes(
+        mut self,
+        max_duplicate_detection_bytes: usize,
+    ) -> Self {
+        let inner = self
+            .inner
+            .with_max_duplicate_detection_bytes(max_duplicate_detection_bytes);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the normalization of object entry names before detecting duplicates,
+    /// see [`KeyNormalization`] for details.
+    ///
+    /// The normalization only takes effect once duplicate object entry names are disallowed.
+    ///
+    /// The validator also keeps the decoded spelling of each name to report it in
+    /// [`Error::DuplicateObjectEntryName`] no matter whether the reader could look back at the source,
+    /// which counts toward [`Validator::with_max_duplicate_detection_bytes`].
+    #[cfg(feature = "unicode-normalization")]
+    pub fn with_key_normalization(mut self, key_normalization: KeyNormalization) -> Self {
+        let inner = self.inner.with_key_normalization(key_normalization);
+        self.inner = inner;
+        self
+    }
+
     /// Disallows duplicate object entry names.
+    ///
+    /// The names are compared by their keyed fingerprints,
+    /// see [`Validator::with_max_duplicate_detection_bytes`].
+    /// Once two fingerprints collide, the earlier name is decoded from the source to confirm the duplicate,
+    /// but the readers which could not look back at the consumed bytes,
+    /// such as [`IoRead`] and [`FeedRead`] for the names in the earlier chunks,
+    /// report the collision as a duplicate without confirming it.
+    /// Such a false positive happens with a probability of roughly `n^2 / 2^65` for `n` names in an object.
     pub fn disallow_duplicate_object_entry_name(mut self) -> Self {
         let inner = self.inner.disallow_duplicate_object_entry_name();
         self.inner = inner;
         self
     }
 
+    /// Sets the policy of which object entry names used for prototype pollution are rejected,
+    /// see [`PrototypePollutionPolicy`] for details.
+    pub fn with_prototype_pollution_policy(
+        mut self,
+        prototype_pollution_policy: PrototypePollutionPolicy,
+    ) -> Self {
+        let inner = self
+            .inner
+            .with_prototype_pollution_policy(prototype_pollution_policy);
+        self.inner = inner;
+        self
+    }
+
+    /// Adds a policy of which object entry names are allowed or forbidden,
+    /// see [`KeyPolicy`] for details.
+    ///
+    /// This could be called multiple times,
+    /// and the names must be accepted by all the policies.
+    pub fn with_key_policy(mut self, key_policy: KeyPolicy) -> Self {
+        let inner = self.inner.with_key_policy(key_policy);
+        self.inner = inner;
+        self
+    }
+
     /// Validates the JSON payload in a single call, and consumes current [`Validator`] instance.
     ///
     /// # Returns
@@ -220,12 +642,81 @@ impl<R: Read> Validator<R> {
         self.inner.validate()
     }
 
+    /// Registers a custom rule, which is invoked for each token after the built-in constraints,
+    /// see [`rule::Rule`].
+    ///
+    /// The rules are invoked in the order they are registered.
+    pub fn with_rule<T: rule::Rule + 'static>(mut self, rule: T) -> Self {
+        let inner = self.inner.with_rule(Box::new(rule));
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the maximum bytes allocated by the validator itself,
+    /// which are the stacks that track the nesting, the hash tables for duplicate detection,
+    /// the current object entry names of each level, the string buffers,
+    /// and the buffer of the reader, such as the internal buffer of [`IoRead`]
+    /// and the chunks fed to [`FeedRead`] that have not been released yet.
+    ///
+    /// Without limits on the depth and the object entries,
+    /// a hostile payload could make the validator allocate proportionally to the payload size,
+    /// this limit puts a ceiling on it.
+    ///
+    /// The memory is checked after each token, including the token peeked at the end of the steps,
+    /// and a string or a number is rejected while it is buffered
+    /// once it exceeds the memory left by the rest of the validator.
+    /// The buffers grow by doubling, so they might still exceed the limit by the last growth
+    /// before the token is rejected.
+    pub fn with_max_validator_memory(mut self, max_validator_memory: usize) -> Self {
+        let inner = self.inner.with_max_validator_memory(max_validator_memory);
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the maximum number of errors collected by [`Validator::validate_all`],
+    /// the validation stops once the limit is reached.
+    ///
+    /// The default limit is 100, so a hostile payload with countless violations
+    /// could not make the validator allocate more than its own size for the errors.
+    /// The first violation always ends the validation, so `0` is the same as `1`.
+    pub fn with_max_errors(mut self, max_errors: usize) -> Self {
+        let inner = self.inner.with_max_errors(max_errors);
+        self.inner = inner;
+        self
+    }
+
+    /// Validates the JSON payload in a single call, and consumes current [`Validator`] instance,
+    /// but keeps going after constraint violations instead of stopping at the first one.
+    ///
+    /// Only constraint violations such as [`Error::MaxStringLengthExceeded`] are collected,
+    /// errors that prevent the validation from going on,
+    /// such as invalid JSON syntax, [`Error::MaxDepthExceeded`], [`Error::MaxPayloadSizeExceeded`],
+    /// and the limits checked while a token is read, such as [`Error::MaxStringRawLengthExceeded`]
+    /// and [`Error::MaxNumberLengthExceeded`], are still fatal, so the nesting beyond the maximum depth never grows the validator.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the JSON payload is valid and did not violate any constraints.
+    /// * `Err` - All constraint violations in the order they occurred,
+    ///   the last one is the fatal error if the validation could not finish.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error`] - If the JSON payload is invalid or violates any constraints.
+    ///
+    /// At most 100 errors are collected by default, use [`Validator::with_max_errors`] to change it.
+    /// The strings, names and keys copied into the collected errors only keep their first 64 bytes,
+    /// and their paths only keep the first 256 bytes.
+    pub fn validate_all(self) -> Result<(), Vec<validator::ValidatorError>> {
+        self.inner.validate_all()
+    }
+
     /// Validates the JSON payload in multiple calls.
     ///
     /// # Arguments
     ///
     /// * `steps` - The number of steps to validate the JSON payload,
-    ///             roughly corresponds to the number of tokens processed.
+    ///   roughly corresponds to the number of tokens processed.
     ///
     /// # Returns
     ///
@@ -249,18 +740,148 @@ impl<R: Read> Validator<R> {
     pub fn validate_with_steps(&mut self, steps: usize) -> Result<bool, validator::ValidatorError> {
         self.inner.validate_with_steps(steps)
     }
+
+    /// Takes a [`Checkpoint`] of the progress, such as between the calls of
+    /// [`Validator::validate_with_steps`] or [`Validator::feed`],
+    /// which is restored by [`Validator::from_checkpoint`].
+    ///
+    /// The bytes before [`Checkpoint::position`] have been validated,
+    /// even if the reader has read ahead of it.
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.inner.checkpoint()
+    }
+
+    /// Consumes current [`Validator`] instance and returns an [`event::EventReader`]
+    /// that yields the events of the JSON payload while validating it.
+    ///
+    /// The constraints configured on this validator are also enforced by the returned reader.
+    pub fn into_events(self) -> event::EventReader<R> {
+        event::EventReader::new(self.inner)
+    }
+}
+
+impl Validator<FeedRead> {
+    /// Feeds the next chunk of the JSON payload, and validates it as far as possible.
+    ///
+    /// The chunks could be split anywhere, even in the middle of a string,
+    /// an escape sequence or a number, and the partial token is kept until the next chunk.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Progress::NeedMoreInput)` - If the JSON value is not complete yet.
+    /// * `Ok(Progress::Complete)` - If a complete JSON value has been validated,
+    ///   but it is not finished until [`Validator::finish`] is called.
+    /// * `Err` - If the JSON payload is invalid or violates any constraints,
+    ///   and the validator should not be fed anymore.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Progress, Error> {
+        self.inner.feed(chunk)
+    }
+
+    /// Marks the end of the JSON payload, and validates the rest of it.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error`] - If the JSON payload is incomplete, invalid or violates any constraints.
+    pub fn finish(self) -> Result<(), Error> {
+        self.inner.finish()
+    }
+}
+
+impl<R: std::io::Read> Validator<IoRead<R>> {
+    /// Validates the JSON payload read from a non-blocking reader as far as possible.
+    ///
+    /// Once the reader returns [`std::io::ErrorKind::WouldBlock`],
+    /// the partial token is kept and this method returns `Ok(false)`,
+    /// call it again once the reader is readable.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - If the end of input is reached, and the validation is finished and no errors.
+    /// * `Ok(false)` - If the reader would block, and you should call this function again.
+    /// * `Err` - If the reader fails, or the JSON payload is invalid or violates any constraints.
+    ///
+    /// # WARNING
+    ///
+    /// The validator will be invalidated once this method
+    /// returns an `Err` or `Ok(true)`,
+    /// and calling any methods for [`Validator`] instance is undefined behavior.
+    pub fn validate_nonblocking(&mut self) -> Result<bool, Error> {
+        self.inner.validate_nonblocking()
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+impl<S: AsyncSource + Unpin> Validator<AsyncIoRead<S>> {
+    /// Validates the JSON payload read from the asynchronous source,
+    /// which yields to the executor whenever the source has no bytes available.
+    ///
+    /// The source is read in chunks, and each chunk is validated as soon as it arrives,
+    /// so the payload is never buffered as a whole.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error`] - If the source fails, or the JSON payload is invalid or violates any constraints.
+    pub async fn validate_async(self) -> Result<(), Error> {
+        self.inner.validate_async().await
+    }
 }
 
 /// Creates a new `Validator` instance with the given slice of bytes without any constraints.
-pub fn from_slice(slice: &[u8]) -> Validator<SliceRead> {
+pub fn from_slice(slice: &[u8]) -> Validator<SliceRead<'_>> {
     Validator::new(SliceRead::new(slice))
 }
 
 /// Creates a new `Validator` instance with the given `&str` without any constraints.
-pub fn from_str(string: &str) -> Validator<StrRead> {
+pub fn from_str(string: &str) -> Validator<StrRead<'_>> {
     Validator::new(StrRead::new(string))
 }
 
+/// Creates a new `Validator` instance without any constraints,
+/// which is fed with the chunks of the JSON payload by [`Validator::feed`].
+///
+/// # Examples
+///
+/// ```rust
+/// use json_threat_protection::{self as jtp, Progress};
+///
+/// let mut validator = jtp::from_chunks().with_max_depth(8);
+/// assert_eq!(validator.feed(br#"{"key": "val"#).unwrap(), Progress::NeedMoreInput);
+/// assert_eq!(validator.feed(br#"ue"}"#).unwrap(), Progress::Complete);
+/// validator.finish().unwrap();
+/// ```
+pub fn from_chunks() -> Validator<FeedRead> {
+    Validator::new(FeedRead::new())
+}
+
+/// Creates a new `Validator` instance with the given [`tokio::io::AsyncRead`] without any constraints,
+/// enabled by the `tokio` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// async fn validate_body<R: tokio::io::AsyncRead + Unpin>(body: R) -> Result<(), json_threat_protection::Error> {
+///     json_threat_protection::from_tokio_reader(body)
+///         .with_max_depth(32)
+///         .validate_async()
+///         .await
+/// }
+/// ```
+#[cfg(feature = "tokio")]
+pub fn from_tokio_reader<R: tokio::io::AsyncRead + Unpin>(
+    reader: R,
+) -> Validator<AsyncIoRead<read::TokioIo<R>>> {
+    Validator::new(AsyncIoRead::new(read::TokioIo(reader)))
+}
+
+/// Creates a new `Validator` instance with the given [`futures_io::AsyncRead`] without any constraints,
+/// enabled by the `futures-io` feature.
+#[cfg(feature = "futures-io")]
+pub fn from_async_reader<R: futures_io::AsyncRead + Unpin>(
+    reader: R,
+) -> Validator<AsyncIoRead<read::FuturesIo<R>>> {
+    Validator::new(AsyncIoRead::new(read::FuturesIo(reader)))
+}
+
 /// Creates a new `Validator` instance with the given reader without any constraints.
 ///
 /// # Arguments
@@ -272,17 +893,19 @@ pub fn from_str(string: &str) -> Validator<StrRead> {
 /// Constructing a `Validator` instance with a reader is slower than
 /// using [`from_slice`] or [`from_str`] functions.
 ///
-/// And also it is recommended to use the [`std::io::BufReader`] to wrap the reader
-/// to improve the performance instead of using the reader directly.
+/// The reader is read in chunks into an internal buffer of 8 KiB,
+/// so there is no need to wrap it with [`std::io::BufReader`].
+/// Use [`read::IoRead::with_capacity`] and [`Validator::new`] to choose another buffer size.
+///
+/// The reads interrupted by signals are retried,
+/// and a non-blocking reader could be validated by [`Validator::validate_nonblocking`].
 ///
 /// # Examples
 ///
 /// ```rust
-/// use std::io::BufReader;
-///
-/// fn validate_from_reader<R: std::io::Read>(reader: R) -> Result<(), json_threat_protection::Error> {
-///     let buf_reader = BufReader::new(std::fs::File::open("huge.json").unwrap());
-///     json_threat_protection::from_reader(buf_reader).validate()
+/// fn validate_from_file() -> Result<(), json_threat_protection::Error> {
+///     let file = std::fs::File::open("huge.json").unwrap();
+///     json_threat_protection::from_reader(file).validate()
 /// }
 /// ```
 pub fn from_reader<R: std::io::Read>(reader: R) -> Validator<IoRead<R>> {
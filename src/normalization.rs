@@ -0,0 +1,112 @@
+#[cfg(feature = "unicode-normalization")]
+use caseless::Caseless;
+#[cfg(feature = "unicode-normalization")]
+use std::borrow::Cow;
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::{is_nfc_quick, is_nfkc_quick, IsNormalized, UnicodeNormalization};
+
+/// Unicode normalization form applied to object entry names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NormalizationForm {
+    /// Canonical composition, such as `"e\u0301"` to `"\u00e9"`
+    Nfc,
+
+    /// Compatibility composition, which also maps the compatibility characters,
+    /// such as the fullwidth `"\uff21"` to `"A"` and `"\ufb01"` to `"fi"`
+    Nfkc,
+}
+
+/// Normalization of object entry names before detecting duplicates,
+/// which takes effect with the `unicode-normalization` feature.
+///
+/// The JSON specification compares object entry names by their code points,
+/// so `"\u00e9"` and `"e\u0301"` are different names,
+/// but the downstream which normalizes the names would see them as duplicates,
+/// and might keep either of them.
+/// Normalizing the names before detecting duplicates rejects such payloads,
+/// and [`crate::Error::DuplicateObjectEntryName`] reports both spellings.
+///
+/// The normalization only affects the duplicate detection,
+/// the other constraints still see the names as they are.
+///
+/// The confusable characters, such as the Cyrillic `"\u0430"` and the Latin `"a"`,
+/// are out of scope, they are different names even after the normalization and the case folding.
+///
+/// The default normalization keeps the names as they are.
+/// Without the feature, the validation fails with [`crate::Error::FeatureNotEnabled`]
+/// if the [`crate::ValidatorConfig`] sets any other normalization.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "unicode-normalization")]
+/// # {
+/// use json_threat_protection::{self as jtp, KeyNormalization, NormalizationForm};
+///
+/// let normalization = KeyNormalization {
+///     form: Some(NormalizationForm::Nfc),
+///     case_fold: true,
+/// };
+///
+/// let err = jtp::from_str(r#"{"caf\u00e9": 1, "CAFE\u0301": 2}"#)
+///     .disallow_duplicate_object_entry_name()
+///     .with_key_normalization(normalization)
+///     .validate()
+///     .unwrap_err();
+///
+/// match err {
+///     jtp::Error::DuplicateObjectEntryName { key, earlier_key, .. } => {
+///         assert_eq!(key, "CAFE\u{301}");
+///         assert_eq!(earlier_key.as_deref(), Some("caf\u{e9}"));
+///     }
+///     _ => unreachable!(),
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct KeyNormalization {
+    /// Unicode normalization form, or `None` to keep the code points as they are
+    pub form: Option<NormalizationForm>,
+
+    /// Whether to apply the full Unicode case folding after the normalization,
+    /// so `"Straße"` and `"STRASSE"` are the same name,
+    /// the folded name is normalized again by the `form`.
+    pub case_fold: bool,
+}
+
+#[cfg(feature = "unicode-normalization")]
+impl KeyNormalization {
+    /// Normalizes the decoded object entry name,
+    /// which is borrowed if it is already normalized.
+    pub(crate) fn normalize<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        let name = self.apply_form(Cow::Borrowed(name));
+
+        // Folding the case might break the normalization, such as `"\u01f0"` to `"j\u030c"`.
+        if self.case_fold && name.chars().default_case_fold().ne(name.chars()) {
+            let folded = name.chars().default_case_fold().collect::<String>();
+            return Cow::Owned(self.apply_form(Cow::Owned(folded)).into_owned());
+        }
+
+        name
+    }
+
+    fn apply_form<'a>(&self, name: Cow<'a, str>) -> Cow<'a, str> {
+        match self.form {
+            None => name,
+            Some(NormalizationForm::Nfc) => match is_nfc_quick(name.chars()) {
+                IsNormalized::Yes => name,
+                _ => Cow::Owned(name.nfc().collect()),
+            },
+            Some(NormalizationForm::Nfkc) => match is_nfkc_quick(name.chars()) {
+                IsNormalized::Yes => name,
+                _ => Cow::Owned(name.nfkc().collect()),
+            },
+        }
+    }
+}
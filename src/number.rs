@@ -0,0 +1,188 @@
+/// Policy of which numbers are accepted by the validator.
+///
+/// The JSON specification allows numbers with any precision,
+/// but parsers might not be able to represent such numbers in their native types,
+/// such as the [serde_json](https://crates.io/crates/serde_json)
+/// without `arbitrary_precision` feature enabled.
+/// So this policy could be used to reject numbers that cannot be processed by the downstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NumberPolicy {
+    /// Accepts numbers with any precision, this is the default policy.
+    #[default]
+    ArbitraryPrecision,
+
+    /// Accepts integers only, numbers with fraction or exponent are rejected.
+    IntegerOnly,
+
+    /// Accepts integers that fit in [`i64`], numbers with fraction or exponent are rejected.
+    I64,
+
+    /// Accepts integers that fit in [`u64`], numbers with fraction or exponent are rejected.
+    U64,
+
+    /// Accepts numbers that round-trip through [`f64`] without loss of precision,
+    /// which means the shortest representation of the parsed [`f64`]
+    /// has the same value as the literal, so `0.1` is accepted
+    /// but `3.14159265358979323846` and `1e400` are rejected.
+    F64,
+}
+
+impl NumberPolicy {
+    /// Check if the number literal is accepted by this policy.
+    pub(crate) fn accepts(&self, literal: &[u8], parts: &NumberParts) -> bool {
+        let is_integer = parts.fraction.is_empty() && parts.exponent.is_empty();
+
+        match self {
+            NumberPolicy::ArbitraryPrecision => true,
+            NumberPolicy::IntegerOnly => is_integer,
+            NumberPolicy::I64 => is_integer && parse::<i64>(literal).is_some(),
+            NumberPolicy::U64 => is_integer && parse::<u64>(literal).is_some(),
+            NumberPolicy::F64 => match parse::<f64>(literal) {
+                Some(value) if value.is_finite() => {
+                    let shortest = format!("{:e}", value);
+                    Decimal::new(parts) == Decimal::new(&NumberParts::new(shortest.as_bytes()))
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+fn parse<T: std::str::FromStr>(literal: &[u8]) -> Option<T> {
+    std::str::from_utf8(literal).ok()?.parse().ok()
+}
+
+/// Components of a JSON number literal.
+pub struct NumberParts<'a> {
+    /// Whether the number has a minus sign
+    pub negative: bool,
+
+    /// Digits before the period
+    pub integer: &'a [u8],
+
+    /// Digits after the period, empty if there is no fraction
+    pub fraction: &'a [u8],
+
+    /// Whether the exponent has a minus sign
+    pub exponent_negative: bool,
+
+    /// Digits after the exponent sign, empty if there is no exponent
+    pub exponent: &'a [u8],
+}
+
+impl<'a> NumberParts<'a> {
+    /// Split a number literal into its components.
+    ///
+    /// The literal is assumed to be a valid JSON number
+    /// that has been checked by [`crate::read::Read::next_number`].
+    pub fn new(literal: &'a [u8]) -> Self {
+        let (negative, literal) = match literal.strip_prefix(b"-") {
+            Some(literal) => (true, literal),
+            None => (false, literal),
+        };
+
+        let (mantissa, exponent) = match literal.iter().position(|&b| b == b'e' || b == b'E') {
+            Some(i) => (&literal[..i], &literal[i + 1..]),
+            None => (literal, &literal[literal.len()..]),
+        };
+        let (exponent_negative, exponent) = match exponent.first() {
+            Some(b'-') => (true, &exponent[1..]),
+            Some(b'+') => (false, &exponent[1..]),
+            _ => (false, exponent),
+        };
+
+        let (integer, fraction) = match mantissa.iter().position(|&b| b == b'.') {
+            Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+            None => (mantissa, &mantissa[mantissa.len()..]),
+        };
+
+        NumberParts {
+            negative,
+            integer,
+            fraction,
+            exponent_negative,
+            exponent,
+        }
+    }
+
+    /// Absolute value of the exponent, saturates at [`usize::MAX`].
+    pub fn exponent_magnitude(&self) -> usize {
+        self.exponent.iter().fold(0usize, |acc, &digit| {
+            acc.saturating_mul(10)
+                .saturating_add((digit - b'0') as usize)
+        })
+    }
+}
+
+/// Normalized decimal value of a number, `digits * 10^exponent`,
+/// so that two literals with the same value are equal.
+struct Decimal<'a> {
+    negative: bool,
+
+    /// Significant digits without leading and trailing zeros, might span two slices
+    digits: (&'a [u8], &'a [u8]),
+
+    exponent: i128,
+}
+
+impl PartialEq for Decimal<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.negative == other.negative
+            && self.exponent == other.exponent
+            && self.digits.0.iter().chain(self.digits.1).eq(other
+                .digits
+                .0
+                .iter()
+                .chain(other.digits.1))
+    }
+}
+
+impl<'a> Decimal<'a> {
+    fn new(parts: &NumberParts<'a>) -> Self {
+        let mut integer = parts.integer;
+        let mut fraction = parts.fraction;
+
+        let magnitude = parts.exponent_magnitude() as i128;
+        let mut exponent = match parts.exponent_negative {
+            true => -magnitude,
+            false => magnitude,
+        };
+        exponent -= fraction.len() as i128;
+
+        while let Some((&b'0', rest)) = fraction.split_last() {
+            fraction = rest;
+            exponent += 1;
+        }
+        if fraction.is_empty() {
+            while let Some((&b'0', rest)) = integer.split_last() {
+                integer = rest;
+                exponent += 1;
+            }
+        }
+
+        while let Some((&b'0', rest)) = integer.split_first() {
+            integer = rest;
+        }
+        if integer.is_empty() {
+            while let Some((&b'0', rest)) = fraction.split_first() {
+                fraction = rest;
+            }
+        }
+
+        // Zero has neither sign nor exponent.
+        if integer.is_empty() && fraction.is_empty() {
+            return Decimal {
+                negative: false,
+                digits: (integer, fraction),
+                exponent: 0,
+            };
+        }
+
+        Decimal {
+            negative: parts.negative,
+            digits: (integer, fraction),
+            exponent,
+        }
+    }
+}
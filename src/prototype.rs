@@ -0,0 +1,38 @@
+/// Object entry names that could pollute the prototype of JavaScript objects.
+pub(crate) const PROTOTYPE_POLLUTION_KEYS: [&str; 3] = ["__proto__", "constructor", "prototype"];
+
+/// Policy of which object entry names used for prototype pollution are rejected.
+///
+/// JavaScript services that merge the parsed JSON into existing objects,
+/// such as `Object.assign` or the deep merge helpers,
+/// could have the prototype of every object modified by an entry named `__proto__`,
+/// or by the `constructor` and `prototype` entries.
+/// This policy rejects such names at any depth before the payload reaches the downstream.
+///
+/// The names are compared after the escape sequences are decoded,
+/// so `"\u005f_proto__"` is the same name as `"__proto__"` to JavaScript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PrototypePollutionPolicy {
+    /// Accepts all names, this is the default policy.
+    #[default]
+    Allow,
+
+    /// Rejects `__proto__`, `constructor` and `prototype` written without escape sequences.
+    RejectUnescaped,
+
+    /// Rejects `__proto__`, `constructor` and `prototype`,
+    /// including the names that only become one of them after the escape sequences are decoded.
+    RejectAll,
+}
+
+impl PrototypePollutionPolicy {
+    /// Check if the decoded object entry name is rejected by this policy.
+    pub(crate) fn rejects(&self, name: &str, escaped: bool) -> bool {
+        match self {
+            PrototypePollutionPolicy::Allow => false,
+            PrototypePollutionPolicy::RejectUnescaped if escaped => false,
+            _ => PROTOTYPE_POLLUTION_KEYS.contains(&name),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use super::{FeedRead, Position, Read, ReadError};
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Size of the chunks read from the asynchronous source.
+const CHUNK_BYTES: usize = 8 * 1024;
+
+/// An asynchronous source of bytes, which is read by [`AsyncIoRead`].
+///
+/// It is implemented by [`TokioIo`] with the `tokio` feature,
+/// and [`FuturesIo`] with the `futures-io` feature.
+pub trait AsyncSource {
+    /// Attempt to read bytes into `buf`, returns `0` once the end of input is reached.
+    fn poll_read_chunk(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>>;
+}
+
+/// Wrapper of [`tokio::io::AsyncRead`], enabled by the `tokio` feature.
+#[cfg(feature = "tokio")]
+pub struct TokioIo<R>(pub R);
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin> AsyncSource for TokioIo<R> {
+    fn poll_read_chunk(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut buf = tokio::io::ReadBuf::new(buf);
+        match Pin::new(&mut self.0).poll_read(cx, &mut buf) {
+            Poll::Ready(Ok(())) => Poll::Ready(Ok(buf.filled().len())),
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Wrapper of [`futures_io::AsyncRead`], enabled by the `futures-io` feature.
+#[cfg(feature = "futures-io")]
+pub struct FuturesIo<R>(pub R);
+
+#[cfg(feature = "futures-io")]
+impl<R: futures_io::AsyncRead + Unpin> AsyncSource for FuturesIo<R> {
+    fn poll_read_chunk(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+/// A reader for an [`AsyncSource`] which implements the [`Read`] trait.
+///
+/// The chunks read from the source are fed to a [`FeedRead`],
+/// so the validator never waits for the source while reading a token,
+/// see [`crate::Validator::validate_async`].
+pub struct AsyncIoRead<S> {
+    source: S,
+    feed: FeedRead,
+    chunk: Box<[u8]>,
+}
+
+impl<S> AsyncIoRead<S> {
+    pub fn new(source: S) -> Self {
+        AsyncIoRead {
+            source,
+            feed: FeedRead::new(),
+            chunk: vec![0; CHUNK_BYTES].into_boxed_slice(),
+        }
+    }
+
+    /// Whether enough bytes have been read to lex the partial token again.
+    pub fn is_ready(&self) -> bool {
+        self.feed.is_ready()
+    }
+}
+
+impl<S: AsyncSource + Unpin> AsyncIoRead<S> {
+    /// Read the next chunk from the source,
+    /// returns `false` once the input is finished.
+    pub async fn fill(&mut self) -> Result<bool, ReadError> {
+        if self.feed.is_finished() {
+            return Ok(false);
+        }
+
+        let source = &mut self.source;
+        let chunk = &mut self.chunk;
+        let len = poll_fn(|cx| Pin::new(&mut *source).poll_read_chunk(cx, chunk))
+            .await
+            .map_err(|err| ReadError::IoError(err, self.feed.position()))?;
+
+        if len == 0 {
+            self.feed.finish();
+            return Ok(false);
+        }
+
+        self.feed.feed(&self.chunk[..len]);
+        Ok(true)
+    }
+}
+
+impl<S> Read for AsyncIoRead<S> {
+    fn position(&self) -> Position {
+        self.feed.position()
+    }
+
+    fn set_max_payload_bytes(&mut self, limit: usize) {
+        self.feed.set_max_payload_bytes(limit)
+    }
+
+    fn buffer_capacity(&self) -> usize {
+        self.feed.buffer_capacity() + self.chunk.len()
+    }
+
+    fn consumed_slice(&self, start: usize, end: usize) -> Option<&[u8]> {
+        self.feed.consumed_slice(start, end)
+    }
+
+    fn resume_at(&mut self, position: Position) {
+        self.feed.resume_at(position)
+    }
+
+    fn mark(&mut self) {
+        self.feed.mark()
+    }
+
+    fn rewind(&mut self) {
+        self.feed.rewind()
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, ReadError> {
+        self.feed.peek()
+    }
+
+    fn next(&mut self) -> Result<Option<u8>, ReadError> {
+        self.feed.next()
+    }
+}
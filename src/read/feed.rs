@@ -0,0 +1,180 @@
+use super::{Position, Read, ReadError};
+
+/// Partial tokens shorter than this are lexed again on every chunk,
+/// the longer ones only once their buffered bytes have doubled.
+const EAGER_RETRY_BYTES: usize = 128;
+
+/// A reader for the chunks of bytes pushed by the caller, which implements the [`Read`] trait.
+///
+/// Once the fed bytes are exhausted, the reader returns [`ReadError::NeedMoreInput`]
+/// until [`FeedRead::finish`] is called, and the validator rewinds to the start of the partial token
+/// to lex it again once more bytes are fed.
+///
+/// Only the bytes since the start of the partial token are retained,
+/// and a long partial token is lexed again only once its buffered bytes have doubled,
+/// so a large token split into many small chunks is still lexed in linear time.
+pub struct FeedRead {
+    /// Fed bytes which have not been released yet.
+    buf: Vec<u8>,
+
+    /// Offset of the first byte of `buf` from the beginning of the entire input.
+    base: usize,
+
+    /// Index of the next byte in `buf`.
+    cursor: usize,
+    position: Position,
+
+    /// Index in `buf` and position of the last mark.
+    mark: usize,
+    mark_position: Position,
+
+    /// Number of bytes after the mark required to lex the partial token again.
+    retry_at: usize,
+
+    /// Whether no more bytes will be fed.
+    finished: bool,
+
+    /// Number of bytes that are still allowed to be fed.
+    remaining: usize,
+}
+
+impl FeedRead {
+    pub fn new() -> Self {
+        FeedRead {
+            buf: Vec::new(),
+            base: 0,
+            cursor: 0,
+            position: Position::default(),
+            mark: 0,
+            mark_position: Position::default(),
+            retry_at: 0,
+            finished: false,
+            remaining: usize::MAX,
+        }
+    }
+
+    /// Append a chunk of bytes to the input.
+    ///
+    /// The bytes before the last mark are released before appending the chunk.
+    /// The input is considered finished once more bytes than the maximum payload size are fed,
+    /// so that the validator could report the payload is too large.
+    pub fn feed(&mut self, chunk: &[u8]) {
+        if self.finished {
+            return;
+        }
+
+        if self.mark > 0 {
+            self.buf.drain(..self.mark);
+            self.base += self.mark;
+            self.cursor -= self.mark;
+            self.mark = 0;
+        }
+
+        let len = chunk.len().min(self.remaining);
+        self.buf.extend_from_slice(&chunk[..len]);
+        self.remaining -= len;
+        if self.remaining == 0 {
+            self.finished = true;
+        }
+    }
+
+    /// Mark the end of the input.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Whether the input is finished.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Whether enough bytes have been fed to lex the partial token again.
+    pub fn is_ready(&self) -> bool {
+        self.finished || self.buf.len() - self.mark >= self.retry_at
+    }
+}
+
+impl Default for FeedRead {
+    fn default() -> Self {
+        FeedRead::new()
+    }
+}
+
+impl Read for FeedRead {
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_max_payload_bytes(&mut self, limit: usize) {
+        let fed = self.base + self.buf.len();
+        self.remaining = limit.saturating_add(1).saturating_sub(fed);
+        if self.remaining == 0 {
+            self.finished = true;
+        }
+    }
+
+    fn buffer_capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    fn consumed_slice(&self, start: usize, end: usize) -> Option<&[u8]> {
+        if start < self.base || start > end || end > self.position.offset {
+            return None;
+        }
+
+        Some(&self.buf[start - self.base..end - self.base])
+    }
+
+    fn resume_at(&mut self, position: Position) {
+        self.base = position.offset;
+        self.position = position;
+        self.mark_position = position;
+    }
+
+    fn mark(&mut self) {
+        self.mark = self.cursor;
+        self.mark_position = self.position;
+        self.retry_at = 0;
+    }
+
+    fn rewind(&mut self) {
+        let pending = self.buf.len() - self.mark;
+        self.retry_at = if pending < EAGER_RETRY_BYTES {
+            pending + 1
+        } else {
+            pending * 2
+        };
+
+        self.cursor = self.mark;
+        self.position = self.mark_position;
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, ReadError> {
+        match self.buf.get(self.cursor) {
+            Some(ch) => Ok(Some(*ch)),
+            None if self.finished => Ok(None),
+            None => Err(ReadError::NeedMoreInput(self.position)),
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<u8>, ReadError> {
+        let ch = match self.peek()? {
+            Some(ch) => ch,
+            None => return Ok(None),
+        };
+        self.cursor += 1;
+
+        match ch {
+            b'\n' => {
+                self.position.line += 1;
+                self.position.column = 1;
+            }
+            _ => {
+                self.position.column += 1;
+            }
+        }
+
+        self.position.offset += 1;
+        Ok(Some(ch))
+    }
+}
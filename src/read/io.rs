@@ -1,24 +1,251 @@
-use super::{Position, Read, ReadError};
+use super::utils::{IS_WHITESPACE, NEED_ESCAPE};
+use super::{
+    check_string, parse_escape_sequence, NumberPart, NumberScan, Position, Read, ReadError,
+    StringSummary, SurrogatePolicy, TokenLimits,
+};
+
+/// Default size of the internal buffer.
+const BUFFER_BYTES: usize = 8 * 1024;
+
+/// Partial tokens shorter than this are lexed again once any byte is read,
+/// the longer ones only once their buffered bytes have doubled.
+const EAGER_RETRY_BYTES: usize = 128;
 
 /// A reader that reads from an object that implements [`std::io::Read`].
 ///
+/// The underlying reader is read in chunks into an internal buffer,
+/// and the whitespace, strings and numbers are scanned over the buffered chunk,
+/// so there is no need to wrap the underlying reader with [`std::io::BufReader`].
+///
+/// Reads interrupted by signals, [`std::io::ErrorKind::Interrupted`], are retried.
+/// A non-blocking reader which returns [`std::io::ErrorKind::WouldBlock`]
+/// makes this reader return [`ReadError::NeedMoreInput`] instead of a fatal error,
+/// and the partial token since the last mark is kept in the buffer,
+/// see [`crate::Validator::validate_nonblocking`].
+///
 /// # Performance
 ///
-/// This reader uses the provided methods of [`Read`] to read from the underlying reader,
-/// which results in a lot of checking on [`std::io::Error`],
-/// so it should be slower than [`super::SliceRead`] and [`super::StrRead`].
+/// This reader still needs to refill the buffer and check the [`std::io::Error`],
+/// so it should be a bit slower than [`super::SliceRead`] and [`super::StrRead`].
 pub struct IoRead<R: std::io::Read> {
     reader: R,
-    ch: Option<u8>,
+    buf: Box<[u8]>,
+
+    /// Index of the next byte in `buf`
+    pos: usize,
+
+    /// Number of bytes filled in `buf`
+    filled: usize,
     position: Position,
+
+    /// Number of bytes that are still allowed to be read from the underlying reader
+    remaining: usize,
+
+    /// Index in `buf` and position of the last mark.
+    mark: Option<usize>,
+    mark_position: Position,
+
+    /// Number of bytes after the mark required to lex the partial token again.
+    retry_at: usize,
+
+    /// Whether the last read from the underlying reader would block.
+    would_block: bool,
 }
 
 impl<R: std::io::Read> IoRead<R> {
     pub fn new(reader: R) -> Self {
+        IoRead::with_capacity(BUFFER_BYTES, reader)
+    }
+
+    /// Creates a reader with the internal buffer of the given capacity, which is at least 1 byte.
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
         IoRead {
             reader,
-            ch: None,
+            buf: vec![0; capacity.max(1)].into_boxed_slice(),
+            pos: 0,
+            filled: 0,
             position: Position::default(),
+            remaining: usize::MAX,
+            mark: None,
+            mark_position: Position::default(),
+            retry_at: 0,
+            would_block: false,
+        }
+    }
+
+    /// Whether the last read from the underlying reader returned [`std::io::ErrorKind::WouldBlock`].
+    pub fn would_block(&self) -> bool {
+        self.would_block
+    }
+
+    /// Read ahead until enough bytes are buffered to lex the partial token again,
+    /// returns `false` if the underlying reader would block before that.
+    pub fn fill_pending(&mut self) -> Result<bool, ReadError> {
+        while let Some(mark) = self.mark {
+            if self.filled - mark >= self.retry_at {
+                break;
+            }
+
+            match self.read_more() {
+                Ok(true) => (),
+                Ok(false) => break,
+                Err(ReadError::NeedMoreInput(_)) => return Ok(false),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(true)
+    }
+
+    /// Refill the buffer if all the buffered bytes are consumed,
+    /// returns `false` once the end of input is reached.
+    fn fill(&mut self) -> Result<bool, ReadError> {
+        if self.pos < self.filled {
+            return Ok(true);
+        }
+
+        self.read_more()
+    }
+
+    /// Read more bytes from the underlying reader after the buffered ones,
+    /// returns `false` once the end of input is reached.
+    fn read_more(&mut self) -> Result<bool, ReadError> {
+        if self.remaining == 0 {
+            return Ok(false);
+        }
+
+        // Release the consumed bytes, except the ones since the mark,
+        // which are read again if the reader would block in the middle of the token.
+        let start = self.mark.unwrap_or(self.pos).min(self.pos);
+        if start > 0 {
+            self.buf.copy_within(start..self.filled, 0);
+            self.pos -= start;
+            self.filled -= start;
+            self.mark = self.mark.map(|mark| mark - start);
+        }
+
+        if self.filled == self.buf.len() {
+            let mut buf = std::mem::take(&mut self.buf).into_vec();
+            buf.resize(self.filled * 2, 0);
+            self.buf = buf.into_boxed_slice();
+        }
+
+        let len = (self.buf.len() - self.filled).min(self.remaining);
+        loop {
+            match self
+                .reader
+                .read(&mut self.buf[self.filled..self.filled + len])
+            {
+                Ok(0) => {
+                    self.would_block = false;
+                    return Ok(false);
+                }
+                Ok(n) if n <= len => {
+                    self.would_block = false;
+                    self.filled += n;
+                    self.remaining -= n;
+                    return Ok(true);
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                    self.would_block = true;
+                    return Err(ReadError::NeedMoreInput(self.position()));
+                }
+                Err(err) => return Err(ReadError::IoError(err, self.position())),
+                _ => {
+                    return Err(ReadError::Bug {
+                        msg: "IoRead.read_more: `self.reader.read()` returned unexpected value"
+                            .to_string(),
+                        position: self.position(),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Consume `n` buffered bytes which are not line feeds.
+    fn consume(&mut self, n: usize) {
+        self.pos += n;
+        self.position.column += n;
+        self.position.offset += n;
+    }
+
+    fn check_buffer(&self, buf: &[u8], limits: TokenLimits) -> Result<(), ReadError> {
+        if buf.len() > limits.max_buffer_bytes {
+            return Err(ReadError::BufferLimitExceeded(self.position()));
+        }
+        Ok(())
+    }
+
+    /// Consume the digits of the `part` and append them to the `scan`.
+    fn next_digits(&mut self, scan: &mut NumberScan, part: NumberPart) -> Result<(), ReadError> {
+        while self.fill()? {
+            let chunk = &self.buf[self.pos..self.filled];
+            // Stop right after the first digit over the limits.
+            let room = scan.room(part).saturating_add(1);
+            let n = chunk
+                .iter()
+                .take(room)
+                .position(|ch| !ch.is_ascii_digit())
+                .unwrap_or(chunk.len().min(room));
+            self.consume(n);
+            scan.push_slice(part, &self.buf[self.pos - n..self.pos], self.position)?;
+
+            if self.pos < self.filled {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_float(&mut self, scan: &mut NumberScan) -> Result<(), ReadError> {
+        if self.next()? != Some(b'.') {
+            return Err(ReadError::Bug {
+                msg: "IoRead.parse_float: assume the first character is a period".to_string(),
+                position: self.position(),
+            });
+        }
+        scan.push(NumberPart::Fraction, b'.', self.position())?;
+
+        match self.peek()? {
+            Some(b'0'..=b'9') => self.next_digits(scan, NumberPart::Fraction)?,
+            Some(_) => return Err(ReadError::NoNumberCharactersAfterFraction(self.position())),
+            None => return Err(ReadError::UnexpectedEndOfInput(self.position())),
+        }
+
+        match self.peek()? {
+            Some(b'e') | Some(b'E') => self.parse_exponent(scan),
+            _ => Ok(()),
+        }
+    }
+
+    fn parse_exponent(&mut self, scan: &mut NumberScan) -> Result<(), ReadError> {
+        match self.next()? {
+            Some(e @ b'e') | Some(e @ b'E') => {
+                scan.push(NumberPart::Exponent, e, self.position())?
+            }
+            _ => {
+                return Err(ReadError::Bug {
+                    msg: "IoRead.parse_exponent: assume the first character is an exponent"
+                        .to_string(),
+                    position: self.position(),
+                })
+            }
+        }
+
+        match self.peek()? {
+            Some(sign @ b'-') | Some(sign @ b'+') => {
+                self.consume(1);
+                scan.push(NumberPart::Exponent, sign, self.position())?;
+            }
+            Some(b'0'..=b'9') => (),
+            Some(_) => return Err(ReadError::NoNumberCharactersAfterExponent(self.position())),
+            None => return Err(ReadError::UnexpectedEndOfInput(self.position())),
+        }
+
+        match self.peek()? {
+            Some(b'0'..=b'9') => self.next_digits(scan, NumberPart::Exponent),
+            Some(_) => Err(ReadError::NoNumberCharactersAfterExponent(self.position())),
+            None => Err(ReadError::UnexpectedEndOfInput(self.position())),
         }
     }
 }
@@ -28,48 +255,63 @@ impl<R: std::io::Read> Read for IoRead<R> {
         self.position
     }
 
-    fn peek(&mut self) -> Result<Option<u8>, ReadError> {
-        if self.ch.is_some() {
-            return Ok(self.ch);
+    fn set_max_payload_bytes(&mut self, limit: usize) {
+        // Drop the buffered bytes beyond the limit,
+        // as if they were never read from the underlying reader.
+        let allowed = limit.saturating_add(1).saturating_sub(self.position.offset);
+        if self.filled - self.pos > allowed {
+            self.filled = self.pos + allowed;
         }
 
-        let mut buf = [0; 1];
-        match self.reader.read(&mut buf) {
-            Ok(0) => Ok(None),
-            Ok(1) => {
-                self.ch = Some(buf[0]);
-                Ok(self.ch)
-            }
-            Err(err) => Err(ReadError::IoError(err, self.position())),
-            _ => Err(ReadError::Bug {
-                msg: "IoRead.peek: `self.reader.read()` returned unexpected value".to_string(),
-                position: self.position(),
-            }),
+        self.remaining = allowed - (self.filled - self.pos);
+    }
+
+    fn buffer_capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    fn resume_at(&mut self, position: Position) {
+        self.position = position;
+        self.mark_position = position;
+    }
+
+    fn mark(&mut self) {
+        self.mark = Some(self.pos);
+        self.mark_position = self.position;
+        self.retry_at = 0;
+    }
+
+    fn rewind(&mut self) {
+        let mark = match self.mark {
+            Some(mark) => mark,
+            None => return,
+        };
+
+        let pending = self.filled - mark;
+        self.retry_at = if pending < EAGER_RETRY_BYTES {
+            pending + 1
+        } else {
+            pending * 2
+        };
+
+        self.pos = mark;
+        self.position = self.mark_position;
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, ReadError> {
+        if !self.fill()? {
+            return Ok(None);
         }
+
+        Ok(Some(self.buf[self.pos]))
     }
 
     fn next(&mut self) -> Result<Option<u8>, ReadError> {
-        let ch = match self.ch {
-            Some(ch) => {
-                self.ch = None;
-                ch
-            }
-            None => {
-                let mut buf = [0; 1];
-                match self.reader.read(&mut buf) {
-                    Ok(0) => return Ok(None),
-                    Ok(1) => buf[0],
-                    Err(err) => return Err(ReadError::IoError(err, self.position())),
-                    _ => {
-                        return Err(ReadError::Bug {
-                            msg: "IoRead.peek: `self.reader.read()` returned unexpected value"
-                                .to_string(),
-                            position: self.position(),
-                        })
-                    }
-                }
-            }
+        let ch = match self.peek()? {
+            Some(ch) => ch,
+            None => return Ok(None),
         };
+        self.pos += 1;
 
         match ch {
             b'\n' => {
@@ -84,4 +326,102 @@ impl<R: std::io::Read> Read for IoRead<R> {
         self.position.offset += 1;
         Ok(Some(ch))
     }
+
+    fn skip_whitespace(&mut self) -> Result<(), ReadError> {
+        while self.fill()? {
+            while self.pos < self.filled && IS_WHITESPACE[self.buf[self.pos] as usize] {
+                self.next()?;
+            }
+
+            if self.pos < self.filled {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn next_number(&mut self, buf: &mut Vec<u8>, limits: TokenLimits) -> Result<(), ReadError> {
+        let mut scan = NumberScan::new(buf, limits);
+        match self.peek()? {
+            Some(b'-') => {
+                self.consume(1);
+                scan.push(NumberPart::Integer, b'-', self.position())?;
+            }
+            Some(b'0'..=b'9') => (),
+            Some(_) => {
+                return Err(ReadError::Bug {
+                    msg:
+                        "IoRead.next_number: assume the first character is a number or a minus sign"
+                            .to_string(),
+                    position: self.position(),
+                })
+            }
+            None => return Err(ReadError::UnexpectedEndOfInput(self.position())),
+        }
+
+        match self.peek()? {
+            Some(b'0') => {
+                self.consume(1);
+                scan.push(NumberPart::Integer, b'0', self.position())?;
+                if matches!(self.peek()?, Some(b'0'..=b'9')) {
+                    return Err(ReadError::LeadingZerosInNumber(self.position()));
+                }
+            }
+            Some(b'1'..=b'9') => self.next_digits(&mut scan, NumberPart::Integer)?,
+            _ => {
+                return Err(ReadError::Bug {
+                    msg: "IoRead.next_number: assume the first character is a number".to_string(),
+                    position: self.position(),
+                })
+            }
+        }
+
+        match self.peek()? {
+            Some(b'.') => self.parse_float(&mut scan),
+            Some(b'e') | Some(b'E') => self.parse_exponent(&mut scan),
+            _ => Ok(()),
+        }
+    }
+
+    fn next_likely_string(
+        &mut self,
+        buf: &mut Vec<u8>,
+        policy: SurrogatePolicy,
+        limits: TokenLimits,
+    ) -> Result<StringSummary, ReadError> {
+        if self.next()? != Some(b'"') {
+            return Err(ReadError::Bug {
+                msg: "IoRead.next_likely_string: assume the first character is a double quote"
+                    .to_string(),
+                position: self.position(),
+            });
+        }
+
+        let start = self.position().offset;
+        let mut summary = StringSummary::default();
+        while self.fill()? {
+            let chunk = &self.buf[self.pos..self.filled];
+            let n = chunk
+                .iter()
+                .position(|ch| NEED_ESCAPE[*ch as usize])
+                .unwrap_or(chunk.len());
+            buf.extend_from_slice(&chunk[..n]);
+            self.consume(n);
+            self.check_buffer(buf, limits)?;
+            check_string(self.position(), start, &summary, limits)?;
+
+            if self.pos == self.filled {
+                continue;
+            }
+
+            match self.next()? {
+                Some(b'"') => return Ok(summary),
+                Some(b'\\') => parse_escape_sequence(self, buf, policy, &mut summary)?,
+                _ => return Err(ReadError::ControlCharacterInString(self.position())),
+            }
+            check_string(self.position(), start, &summary, limits)?;
+        }
+
+        Err(ReadError::UnclosedString(self.position()))
+    }
 }
@@ -1,20 +1,45 @@
-//! Defines the [`Read`] trait, and provided implementations for [`std::io::Read`], [`&str`], and slice for [`u8`].
+//! Defines the [`Read`] trait, and provided implementations for [`std::io::Read`], [`&str`], slice for [`u8`],
+//! and the chunks pushed by the caller.
 
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+mod async_io;
+mod feed;
 mod io;
 mod slice;
 mod str;
 mod utils;
+#[cfg(feature = "futures-io")]
+pub use async_io::FuturesIo;
+#[cfg(feature = "tokio")]
+pub use async_io::TokioIo;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub use async_io::{AsyncIoRead, AsyncSource};
+pub use feed::FeedRead;
 pub use io::IoRead;
 pub use slice::SliceRead;
 pub use str::StrRead;
 use thiserror::Error;
 
-use utils::{decode_hex_sequence, IS_HEX, NEED_ESCAPE};
+use utils::{
+    decode_hex_sequence, decode_simple_escape, decode_surrogate_pair, IS_HEX, NEED_ESCAPE,
+};
+
+/// Return [`ReadError::BufferLimitExceeded`] once the buffer exceeds the limit.
+macro_rules! check_buffer {
+    ($self:ident, $buf:ident, $limits:ident) => {
+        if $buf.len() > $limits.max_buffer_bytes {
+            return Err(ReadError::BufferLimitExceeded($self.position()));
+        }
+    };
+}
 
 macro_rules! parse_number {
-    ($self:ident) => {{
+    ($self:ident, $scan:ident) => {{
         match $self.peek()? {
-            Some(b'-') => $self.discard(),
+            Some(b'-') => {
+                $self.discard();
+                $scan.push(NumberPart::Integer, b'-', $self.position())?;
+            }
             Some(b'0'..=b'9') => (),
             Some(_) => return Err(ReadError::Bug{
                 msg: "macro_rules! parse_number: assume the first character is a number or a minus sign".to_string(),
@@ -30,6 +55,7 @@ macro_rules! parse_number {
                 position: $self.position(),
             }),
         };
+        $scan.push(NumberPart::Integer, first, $self.position())?;
 
         let second = $self.peek()?;
         if second.is_none() {
@@ -42,9 +68,12 @@ macro_rules! parse_number {
 
         loop {
             match $self.peek()? {
-                Some(b'0'..=b'9') => $self.discard(),
-                Some(b'.') => return parse_float!($self),
-                Some(b'e') | Some(b'E') => return parse_exponent!($self),
+                Some(n @ b'0'..=b'9') => {
+                    $self.discard();
+                    $scan.push(NumberPart::Integer, n, $self.position())?;
+                }
+                Some(b'.') => return parse_float!($self, $scan),
+                Some(b'e') | Some(b'E') => return parse_exponent!($self, $scan),
                 _ => break,
             }
         }
@@ -54,24 +83,31 @@ macro_rules! parse_number {
 }
 
 macro_rules! parse_float {
-    ($self:ident) => {{
+    ($self:ident, $scan:ident) => {{
         if $self.next()? != Some(b'.') {
             return Err(ReadError::Bug {
                 msg: "macro_rules! parse_float: assume the first character is a period".to_string(),
                 position: $self.position(),
             });
         }
+        $scan.push(NumberPart::Fraction, b'.', $self.position())?;
 
         match $self.peek()? {
-            Some(b'0'..=b'9') => $self.discard(),
+            Some(n @ b'0'..=b'9') => {
+                $self.discard();
+                $scan.push(NumberPart::Fraction, n, $self.position())?;
+            }
             Some(_) => return Err(ReadError::NoNumberCharactersAfterFraction($self.position())),
             None => return Err(ReadError::UnexpectedEndOfInput($self.position())),
         }
 
         loop {
             match $self.peek()? {
-                Some(b'0'..=b'9') => $self.discard(),
-                Some(b'e') | Some(b'E') => return parse_exponent!($self),
+                Some(n @ b'0'..=b'9') => {
+                    $self.discard();
+                    $scan.push(NumberPart::Fraction, n, $self.position())?;
+                }
+                Some(b'e') | Some(b'E') => return parse_exponent!($self, $scan),
                 _ => break,
             }
         }
@@ -81,17 +117,25 @@ macro_rules! parse_float {
 }
 
 macro_rules! parse_exponent {
-    ($self:ident) => {{
-        if !matches!($self.next()?, Some(b'e') | Some(b'E')) {
-            return Err(ReadError::Bug {
-                msg: "macro_rules! parse_exponent: assume the first character is an exponent"
-                    .to_string(),
-                position: $self.position(),
-            });
+    ($self:ident, $scan:ident) => {{
+        match $self.next()? {
+            Some(e @ b'e') | Some(e @ b'E') => {
+                $scan.push(NumberPart::Exponent, e, $self.position())?
+            }
+            _ => {
+                return Err(ReadError::Bug {
+                    msg: "macro_rules! parse_exponent: assume the first character is an exponent"
+                        .to_string(),
+                    position: $self.position(),
+                })
+            }
         }
 
         match $self.peek()? {
-            Some(b'-') | Some(b'+') => $self.discard(),
+            Some(sign @ b'-') | Some(sign @ b'+') => {
+                $self.discard();
+                $scan.push(NumberPart::Exponent, sign, $self.position())?;
+            }
             Some(b'0'..=b'9') => (),
             Some(_) => return Err(ReadError::NoNumberCharactersAfterExponent($self.position())),
             None => return Err(ReadError::UnexpectedEndOfInput($self.position())),
@@ -103,11 +147,9 @@ macro_rules! parse_exponent {
             None => return Err(ReadError::UnexpectedEndOfInput($self.position())),
         }
 
-        loop {
-            match $self.peek()? {
-                Some(b'0'..=b'9') => $self.discard(),
-                _ => break,
-            }
+        while let Some(n @ b'0'..=b'9') = $self.peek()? {
+            $self.discard();
+            $scan.push(NumberPart::Exponent, n, $self.position())?;
         }
 
         Ok(())
@@ -139,6 +181,7 @@ pub use utils::Position;
 
 #[derive(Debug, Error)]
 /// An error that can occur when reading characters.
+#[non_exhaustive]
 pub enum ReadError {
     /// Unexpected end of input.
     #[error("unexpected end of input ({0})")]
@@ -168,6 +211,14 @@ pub enum ReadError {
     #[error("non hex character in unicode escape sequence ({0})")]
     NonHexCharacterInUnicodeEscape(Position),
 
+    /// A high surrogate escape sequence, such as `\uD800`, which is not followed by a low surrogate.
+    #[error("lone high surrogate in string ({0})")]
+    LoneHighSurrogate(Position),
+
+    /// A low surrogate escape sequence, such as `\uDC00`, which does not follow a high surrogate.
+    #[error("lone low surrogate in string ({0})")]
+    LoneLowSurrogate(Position),
+
     /// Leading zeros in number.
     #[error("leading zeros in number ({0})")]
     LeadingZerosInNumber(Position),
@@ -180,6 +231,42 @@ pub enum ReadError {
     #[error("no number characters after exponent ({0})")]
     NoNumberCharactersAfterExponent(Position),
 
+    /// The bytes available so far are exhausted, but the end of input has not been reached.
+    #[error("need more input ({0})")]
+    NeedMoreInput(Position),
+
+    /// The string or the number is longer than [`TokenLimits::max_buffer_bytes`].
+    #[error("buffer limit exceeded ({0})")]
+    BufferLimitExceeded(Position),
+
+    /// The string is longer than [`TokenLimits::max_raw_bytes`] in the source.
+    #[error("raw length limit exceeded ({0})")]
+    RawLengthExceeded(Position),
+
+    /// The string has more escape sequences than [`TokenLimits::max_escapes`].
+    #[error("escape sequence limit exceeded ({0})")]
+    EscapesExceeded(Position),
+
+    /// The number is longer than [`TokenLimits::max_number_length`].
+    #[error("number length limit exceeded ({0})")]
+    NumberLengthExceeded(Position),
+
+    /// The number has more digits before the period than [`TokenLimits::max_integer_digits`].
+    #[error("integer digits limit exceeded ({0})")]
+    IntegerDigitsExceeded(Position),
+
+    /// The number has more digits after the period than [`TokenLimits::max_fraction_digits`].
+    #[error("fraction digits limit exceeded ({0})")]
+    FractionDigitsExceeded(Position),
+
+    /// The number has more digits in the exponent than [`TokenLimits::max_exponent_digits`].
+    #[error("exponent digits limit exceeded ({0})")]
+    ExponentDigitsExceeded(Position),
+
+    /// The absolute value of the exponent is larger than [`TokenLimits::max_exponent_magnitude`].
+    #[error("exponent magnitude limit exceeded ({0})")]
+    ExponentMagnitudeExceeded(Position),
+
     /// Running into unexpected state.
     #[error("running into unexpected state, please report this issue to the maintainer, ({msg}) ({position})")]
     Bug {
@@ -191,6 +278,297 @@ pub enum ReadError {
     },
 }
 
+/// Policy of the lone surrogates in strings, such as `"\uD800"`.
+///
+/// The JSON specification allows any `\uXXXX` escape sequence,
+/// but an unpaired surrogate is not a Unicode scalar value,
+/// so it could not be decoded into a valid UTF-8 string.
+/// The parsers disagree on them, some reject the string,
+/// some replace it with U+FFFD, and some keep it as WTF-8,
+/// so the validator and the downstream might see different strings.
+///
+/// Keeping the lone surrogates as WTF-8 is not supported,
+/// because the strings are passed to the rules and the events as `&str`,
+/// which must be valid UTF-8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SurrogatePolicy {
+    /// Reject the payload with [`ReadError::LoneHighSurrogate`] or [`ReadError::LoneLowSurrogate`],
+    /// this is the default policy.
+    #[default]
+    Reject,
+
+    /// Replace each lone surrogate with U+FFFD, the replacement character.
+    Replace,
+
+    /// Replace each lone surrogate with U+FFFD like [`SurrogatePolicy::Replace`],
+    /// and pass each of them to [`crate::rule::Rule::on_lone_surrogate`],
+    /// so the payload is accepted unless a rule rejects it.
+    Report,
+}
+
+/// Whether a lone surrogate is a high (leading) or a low (trailing) surrogate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurrogateKind {
+    /// A surrogate in `\uD800..=\uDBFF` which is not followed by a low surrogate.
+    High,
+
+    /// A surrogate in `\uDC00..=\uDFFF` which is not preceded by a high surrogate.
+    Low,
+}
+
+/// A lone surrogate found in a string, which is reported by [`SurrogatePolicy::Report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoneSurrogate {
+    /// Whether the surrogate is a high or a low surrogate.
+    pub kind: SurrogateKind,
+
+    /// The code unit of the surrogate, such as `0xD800`.
+    pub code_unit: u16,
+
+    /// Position right before the backslash of the `\uXXXX` escape sequence.
+    pub position: Position,
+}
+
+impl LoneSurrogate {
+    pub(crate) fn new(kind: SurrogateKind, code_unit: u16, position: Position) -> Self {
+        LoneSurrogate {
+            kind,
+            code_unit,
+            position,
+        }
+    }
+}
+
+/// Summary of a string read by [`Read::next_likely_string`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct StringSummary {
+    /// Number of escape sequences, where a surrogate pair counts as two escape sequences.
+    pub escapes: usize,
+
+    /// Number of lone surrogates replaced with U+FFFD.
+    pub lone_surrogates: usize,
+
+    /// The lone surrogates in the order of the string,
+    /// which are only kept by [`SurrogatePolicy::Report`].
+    pub surrogates: Vec<LoneSurrogate>,
+}
+
+/// Limits of a single token, which are checked while the token is read,
+/// so an oversized token is rejected before it is buffered entirely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TokenLimits {
+    /// Maximum bytes of a string or a number appended to the buffer,
+    /// [`ReadError::BufferLimitExceeded`] is returned once it is exceeded.
+    pub max_buffer_bytes: usize,
+
+    /// Maximum bytes of a string in the source, excluding the quotes,
+    /// [`ReadError::RawLengthExceeded`] is returned once it is exceeded.
+    pub max_raw_bytes: usize,
+
+    /// Maximum number of escape sequences in a string,
+    /// [`ReadError::EscapesExceeded`] is returned once it is exceeded.
+    pub max_escapes: usize,
+
+    /// Maximum length of a number, including the sign, period, and exponent characters,
+    /// [`ReadError::NumberLengthExceeded`] is returned once it is exceeded.
+    pub max_number_length: usize,
+
+    /// Maximum number of digits before the period of a number,
+    /// [`ReadError::IntegerDigitsExceeded`] is returned once it is exceeded.
+    pub max_integer_digits: usize,
+
+    /// Maximum number of digits after the period of a number,
+    /// [`ReadError::FractionDigitsExceeded`] is returned once it is exceeded.
+    pub max_fraction_digits: usize,
+
+    /// Maximum number of digits in the exponent of a number,
+    /// [`ReadError::ExponentDigitsExceeded`] is returned once it is exceeded.
+    pub max_exponent_digits: usize,
+
+    /// Maximum absolute value of the exponent of a number,
+    /// [`ReadError::ExponentMagnitudeExceeded`] is returned once it is exceeded.
+    pub max_exponent_magnitude: usize,
+
+    /// Whether the literal of a number is appended to the buffer,
+    /// otherwise the number is only checked against the limits.
+    pub buffer_numbers: bool,
+}
+
+impl Default for TokenLimits {
+    fn default() -> Self {
+        TokenLimits {
+            max_buffer_bytes: usize::MAX,
+            max_raw_bytes: usize::MAX,
+            max_escapes: usize::MAX,
+            max_number_length: usize::MAX,
+            max_integer_digits: usize::MAX,
+            max_fraction_digits: usize::MAX,
+            max_exponent_digits: usize::MAX,
+            max_exponent_magnitude: usize::MAX,
+            buffer_numbers: true,
+        }
+    }
+}
+
+/// Part of a number literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NumberPart {
+    /// The minus sign and the digits before the period
+    Integer,
+
+    /// The period and the digits after it
+    Fraction,
+
+    /// The exponent character, its sign, and the digits after them
+    Exponent,
+}
+
+/// Counts the characters of a number while it is read,
+/// so the number is rejected at the first character over the [`TokenLimits`],
+/// and appends them to the buffer only if [`TokenLimits::buffer_numbers`] is set.
+pub(crate) struct NumberScan<'a> {
+    buf: &'a mut Vec<u8>,
+    limits: TokenLimits,
+    len: usize,
+    integer_digits: usize,
+    fraction_digits: usize,
+    exponent_digits: usize,
+    exponent_magnitude: usize,
+}
+
+impl<'a> NumberScan<'a> {
+    pub(crate) fn new(buf: &'a mut Vec<u8>, limits: TokenLimits) -> Self {
+        NumberScan {
+            buf,
+            limits,
+            len: 0,
+            integer_digits: 0,
+            fraction_digits: 0,
+            exponent_digits: 0,
+            exponent_magnitude: 0,
+        }
+    }
+
+    /// Number of digits that could still be appended to the `part` without exceeding the limits.
+    pub(crate) fn room(&self, part: NumberPart) -> usize {
+        let (digits, limit) = match part {
+            NumberPart::Integer => (self.integer_digits, self.limits.max_integer_digits),
+            NumberPart::Fraction => (self.fraction_digits, self.limits.max_fraction_digits),
+            NumberPart::Exponent => (self.exponent_digits, self.limits.max_exponent_digits),
+        };
+        let room = limit.saturating_sub(digits);
+        room.min(self.limits.max_number_length.saturating_sub(self.len))
+    }
+
+    /// Appends a character of the `part`, which is read right before the `position`.
+    pub(crate) fn push(
+        &mut self,
+        part: NumberPart,
+        byte: u8,
+        position: Position,
+    ) -> Result<(), ReadError> {
+        self.push_slice(part, &[byte], position)
+    }
+
+    /// Appends the characters of the `part`, which are read right before the `position`.
+    pub(crate) fn push_slice(
+        &mut self,
+        part: NumberPart,
+        bytes: &[u8],
+        position: Position,
+    ) -> Result<(), ReadError> {
+        self.len += bytes.len();
+        if self.len > self.limits.max_number_length {
+            return Err(ReadError::NumberLengthExceeded(position));
+        }
+
+        let digits = bytes.iter().filter(|byte| byte.is_ascii_digit()).count();
+        match part {
+            NumberPart::Integer => {
+                self.integer_digits += digits;
+                if self.integer_digits > self.limits.max_integer_digits {
+                    return Err(ReadError::IntegerDigitsExceeded(position));
+                }
+            }
+            NumberPart::Fraction => {
+                self.fraction_digits += digits;
+                if self.fraction_digits > self.limits.max_fraction_digits {
+                    return Err(ReadError::FractionDigitsExceeded(position));
+                }
+            }
+            NumberPart::Exponent => {
+                self.exponent_digits += digits;
+                if self.exponent_digits > self.limits.max_exponent_digits {
+                    return Err(ReadError::ExponentDigitsExceeded(position));
+                }
+
+                for digit in bytes.iter().filter(|byte| byte.is_ascii_digit()) {
+                    self.exponent_magnitude = self
+                        .exponent_magnitude
+                        .saturating_mul(10)
+                        .saturating_add((digit - b'0') as usize);
+                }
+                if self.exponent_magnitude > self.limits.max_exponent_magnitude {
+                    return Err(ReadError::ExponentMagnitudeExceeded(position));
+                }
+            }
+        }
+
+        if self.limits.buffer_numbers {
+            self.buf.extend_from_slice(bytes);
+            if self.buf.len() > self.limits.max_buffer_bytes {
+                return Err(ReadError::BufferLimitExceeded(position));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Return an error once the string exceeds the raw length or the escape sequences in the `limits`,
+/// where `start` is the offset right after the opening quote,
+/// and `position` is right after the bytes of the string read so far.
+pub(crate) fn check_string(
+    position: Position,
+    start: usize,
+    summary: &StringSummary,
+    limits: TokenLimits,
+) -> Result<(), ReadError> {
+    if position.offset - start > limits.max_raw_bytes {
+        return Err(ReadError::RawLengthExceeded(position));
+    }
+    if summary.escapes > limits.max_escapes {
+        return Err(ReadError::EscapesExceeded(position));
+    }
+    Ok(())
+}
+
+/// Handle a lone surrogate by the policy, the `err` is returned if the policy rejects it.
+pub(crate) fn handle_lone_surrogate(
+    policy: SurrogatePolicy,
+    buf: &mut Vec<u8>,
+    summary: &mut StringSummary,
+    surrogate: LoneSurrogate,
+    err: ReadError,
+) -> Result<(), ReadError> {
+    match policy {
+        SurrogatePolicy::Reject => return Err(err),
+        SurrogatePolicy::Replace => (),
+        SurrogatePolicy::Report => summary.surrogates.push(surrogate),
+    }
+
+    let mut utf8 = [0u8; 4];
+    buf.extend_from_slice(
+        char::REPLACEMENT_CHARACTER
+            .encode_utf8(&mut utf8)
+            .as_bytes(),
+    );
+    summary.lone_surrogates += 1;
+    Ok(())
+}
+
 /// A trait for reading characters from a source.
 ///
 /// # Performance
@@ -204,6 +582,62 @@ pub trait Read {
     /// Get the current position of the reader.
     fn position(&self) -> Position;
 
+    /// Restrict the reader to consume at most `limit + 1` bytes from the source.
+    ///
+    /// The validator calls this method once the maximum payload size is set,
+    /// so the reader could stop consuming the source right after the limit is exceeded
+    /// instead of reading the entire payload, such as an endless stream of whitespace.
+    /// The extra byte lets the validator tell the payload is too large.
+    ///
+    /// The default implementation does nothing,
+    /// in which case the limit is only checked between tokens.
+    fn set_max_payload_bytes(&mut self, _limit: usize) {}
+
+    /// Get the bytes of the source between the given offsets, which have been consumed.
+    ///
+    /// The validator uses this method to look back at the object entry names it has read
+    /// instead of keeping a copy of them.
+    ///
+    /// The default implementation returns `None`,
+    /// in which case the validator could not look back at the source.
+    fn consumed_slice(&self, _start: usize, _end: usize) -> Option<&[u8]> {
+        None
+    }
+
+    /// Mark the current position as the start of the next token.
+    ///
+    /// The validator calls this method before each token,
+    /// so the reader could release the bytes before the mark
+    /// and rewind to it once [`ReadError::NeedMoreInput`] is returned in the middle of the token.
+    ///
+    /// The default implementation does nothing.
+    fn mark(&mut self) {}
+
+    /// Treat the first byte of the source as the byte at the given position of the entire input,
+    /// which is called before reading anything.
+    ///
+    /// The validator calls this method once it is restored from a [`crate::Checkpoint`],
+    /// so the positions and the maximum payload size cover the entire input,
+    /// not only the bytes after the checkpoint.
+    ///
+    /// The default implementation does nothing,
+    /// in which case the positions are relative to the checkpoint.
+    fn resume_at(&mut self, _position: Position) {}
+
+    /// Bytes allocated by the reader to buffer the source,
+    /// which are counted toward the maximum memory of the validator.
+    ///
+    /// The default implementation returns `0`.
+    fn buffer_capacity(&self) -> usize {
+        0
+    }
+
+    /// Rewind to the last mark, so the partial token could be read again once more bytes are available.
+    ///
+    /// The default implementation does nothing,
+    /// which is fine for the readers that never return [`ReadError::NeedMoreInput`].
+    fn rewind(&mut self) {}
+
     /// Peek the next character without consuming it.
     fn peek(&mut self) -> Result<Option<u8>, ReadError>;
 
@@ -251,13 +685,27 @@ pub trait Read {
         Ok(())
     }
 
-    /// Parse a number and allow arbitrary precision.
-    fn next_number(&mut self) -> Result<(), ReadError> {
-        parse_number!(self)
+    /// Parse a number and allow arbitrary precision,
+    /// the literal of the number is appended to the `buf` within the `limits`,
+    /// which are checked after each character of the number,
+    /// unless [`TokenLimits::buffer_numbers`] is unset.
+    fn next_number(&mut self, buf: &mut Vec<u8>, limits: TokenLimits) -> Result<(), ReadError> {
+        let mut scan = NumberScan::new(buf, limits);
+        parse_number!(self, scan)
     }
 
-    /// Parse a string, but not guaranteed to be correct UTF-8.
-    fn next_likely_string(&mut self, buf: &mut Vec<u8>) -> Result<(), ReadError> {
+    /// Parse a string, but not guaranteed to be correct UTF-8,
+    /// the decoded string is appended to the `buf` within the `limits`,
+    /// which are checked after each byte or escape sequence of the source.
+    ///
+    /// The lone surrogates are handled by the `policy`,
+    /// where [`SurrogatePolicy::Report`] keeps them in the returned [`StringSummary`].
+    fn next_likely_string(
+        &mut self,
+        buf: &mut Vec<u8>,
+        policy: SurrogatePolicy,
+        limits: TokenLimits,
+    ) -> Result<StringSummary, ReadError> {
         if self.next()? != Some(b'"') {
             return Err(ReadError::Bug {
                 msg: "Read.next_likely_string: assume the first character is a double quote"
@@ -266,87 +714,114 @@ pub trait Read {
             });
         }
 
+        let start = self.position().offset;
+        let mut summary = StringSummary::default();
         while let Some(byte) = self.next()? {
+            check_buffer!(self, buf, limits);
             if !NEED_ESCAPE[byte as usize] {
                 buf.push(byte);
-                continue;
+            } else {
+                match byte {
+                    b'"' => return Ok(summary),
+                    b'\\' => parse_escape_sequence(self, buf, policy, &mut summary)?,
+                    _ => return Err(ReadError::ControlCharacterInString(self.position())),
+                }
             }
+            check_string(self.position(), start, &summary, limits)?;
+        }
 
-            match byte {
-                b'"' => return Ok(()),
-                b'\\' => {
-                    let mut simple_escape = true;
-
-                    match self.next()? {
-                        Some(b'"') => buf.push(b'"'),
-                        Some(b'\\') => buf.push(b'\\'),
-                        Some(b'/') => buf.push(b'/'),
-                        Some(b'b') => buf.push(b'\x08'),
-                        Some(b'f') => buf.push(b'\x0C'),
-                        Some(b'n') => buf.push(b'\n'),
-                        Some(b'r') => buf.push(b'\r'),
-                        Some(b't') => buf.push(b'\t'),
-                        Some(b'u') => simple_escape = false,
-                        Some(_) => return Err(ReadError::InvalidEscapeSequence(self.position())),
-                        None => return Err(ReadError::UnexpectedEndOfInput(self.position())),
-                    };
-
-                    if simple_escape {
-                        continue;
-                    }
-
-                    let hex = decode_hex_sequence(&next4_hex!(self));
-                    let ch = match hex {
-                        _n @ 0xDC00..=0xDFFF => {
-                            return Err(ReadError::InvalidEscapeSequence(self.position()));
-                        }
-                        n @ 0xD800..=0xDBFF => {
-                            let high = n;
-                            if self.next()? != Some(b'\\') {
-                                return Err(ReadError::InvalidEscapeSequence(self.position()));
-                            }
-                            if self.next()? != Some(b'u') {
-                                return Err(ReadError::InvalidEscapeSequence(self.position()));
-                            }
-                            let low = decode_hex_sequence(&next4_hex!(self));
-                            if !matches!(low, 0xDC00..=0xDFFF) {
-                                return Err(ReadError::InvalidEscapeSequence(self.position()));
-                            }
-
-                            let high = ((high & 0x03FF) << 10) as u32;
-                            let low = (low & 0x03FF) as u32;
-                            let codepoint = 0x10000u32 + high + low;
-
-                            match std::char::from_u32(codepoint) {
-                                Some(ch) => ch,
-                                None => {
-                                    return Err(ReadError::Bug {
-                                        msg:
-                                            "Read.next_likely_string: assume the codepoint is valid"
-                                                .to_string(),
-                                        position: self.position(),
-                                    })
-                                }
-                            }
+        Err(ReadError::UnclosedString(self.position()))
+    }
+}
+
+/// Position right before the backslash, given the position right after it.
+pub(crate) fn before_backslash(position: Position) -> Position {
+    Position {
+        line: position.line,
+        column: position.column.saturating_sub(1),
+        offset: position.offset.saturating_sub(1),
+    }
+}
+
+/// Parse an escape sequence right after the backslash, the decoded character is appended to the `buf`.
+///
+/// The lone surrogates are handled by the `policy`,
+/// and the `summary` counts the escape sequences and the lone surrogates.
+pub(crate) fn parse_escape_sequence<R: Read + ?Sized>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    policy: SurrogatePolicy,
+    summary: &mut StringSummary,
+) -> Result<(), ReadError> {
+    summary.escapes += 1;
+    let mut escape_start = before_backslash(reader.position());
+
+    match reader.next()? {
+        Some(b'u') => (),
+        Some(escape) => match decode_simple_escape(escape) {
+            Some(ch) => {
+                buf.push(ch);
+                return Ok(());
+            }
+            None => return Err(ReadError::InvalidEscapeSequence(reader.position())),
+        },
+        None => return Err(ReadError::UnexpectedEndOfInput(reader.position())),
+    };
+
+    let mut hex = decode_hex_sequence(&next4_hex!(reader));
+    loop {
+        let ch = match hex {
+            0xDC00..=0xDFFF => {
+                let surrogate = LoneSurrogate::new(SurrogateKind::Low, hex, escape_start);
+                let err = ReadError::LoneLowSurrogate(reader.position());
+                return handle_lone_surrogate(policy, buf, summary, surrogate, err);
+            }
+            0xD800..=0xDBFF => {
+                let high = hex;
+                let surrogate = LoneSurrogate::new(SurrogateKind::High, hex, escape_start);
+                let lone = ReadError::LoneHighSurrogate(reader.position());
+                if reader.peek()? != Some(b'\\') {
+                    return handle_lone_surrogate(policy, buf, summary, surrogate, lone);
+                }
+                escape_start = reader.position();
+                reader.discard();
+                summary.escapes += 1;
+
+                match reader.next()? {
+                    Some(b'u') => (),
+                    Some(escape) => match decode_simple_escape(escape) {
+                        Some(ch) => {
+                            handle_lone_surrogate(policy, buf, summary, surrogate, lone)?;
+                            buf.push(ch);
+                            return Ok(());
                         }
-                        n => match std::char::from_u32(n as u32) {
-                            Some(ch) => ch,
-                            None => {
-                                return Err(ReadError::Bug {
-                                    msg: "Read.next_likely_string: assume the codepoint is valid"
-                                        .to_string(),
-                                    position: self.position(),
-                                });
-                            }
-                        },
-                    };
-
-                    buf.extend_from_slice(ch.encode_utf8(&mut [0u8; 4]).as_bytes());
+                        None => return Err(ReadError::InvalidEscapeSequence(reader.position())),
+                    },
+                    None => return Err(ReadError::UnexpectedEndOfInput(reader.position())),
                 }
-                _ => return Err(ReadError::ControlCharacterInString(self.position())),
+
+                let low = decode_hex_sequence(&next4_hex!(reader));
+                if !matches!(low, 0xDC00..=0xDFFF) {
+                    // the next escape sequence is decoded on its own
+                    handle_lone_surrogate(policy, buf, summary, surrogate, lone)?;
+                    hex = low;
+                    continue;
+                }
+
+                decode_surrogate_pair(high, low)
             }
-        }
+            n => match std::char::from_u32(n as u32) {
+                Some(ch) => ch,
+                None => {
+                    return Err(ReadError::Bug {
+                        msg: "parse_escape_sequence: assume the codepoint is valid".to_string(),
+                        position: reader.position(),
+                    })
+                }
+            },
+        };
 
-        Err(ReadError::UnclosedString(self.position()))
+        buf.extend_from_slice(ch.encode_utf8(&mut [0u8; 4]).as_bytes());
+        return Ok(());
     }
 }
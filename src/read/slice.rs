@@ -1,14 +1,28 @@
-use super::utils::{decode_hex_sequence, LineColumnIterator, IS_HEX, IS_WHITESPACE, NEED_ESCAPE};
-use super::{Position, Read, ReadError};
+use super::utils::{
+    decode_hex_sequence, decode_simple_escape, decode_surrogate_pair, LineColumnIterator, IS_HEX,
+    IS_WHITESPACE, NEED_ESCAPE,
+};
+use super::{
+    before_backslash, check_string, handle_lone_surrogate, LoneSurrogate, NumberPart, NumberScan,
+    Position, Read, ReadError, StringSummary, SurrogateKind, SurrogatePolicy, TokenLimits,
+};
 
 /// A reader for slices which implements the [`Read`] trait.
 pub struct SliceRead<'a> {
+    /// The entire input.
+    slice: &'a [u8],
+
+    /// Offset of the first byte of `slice` from the beginning of the entire input.
+    base: usize,
+
     iter: LineColumnIterator<'a, std::slice::Iter<'a, u8>>,
 }
 
 impl<'a> SliceRead<'a> {
     pub fn new(slice: &'a [u8]) -> Self {
         SliceRead {
+            slice,
+            base: 0,
             iter: LineColumnIterator::new(slice.iter()),
         }
     }
@@ -31,10 +45,7 @@ impl<'a> SliceRead<'a> {
     fn next4_no_error(&mut self) -> Option<[u8; 4]> {
         let mut buf = [0; 4];
         for ch in &mut buf {
-            *ch = match self.next_no_error() {
-                Some(ch) => ch,
-                None => return None,
-            };
+            *ch = self.next_no_error()?;
         }
         Some(buf)
     }
@@ -43,10 +54,7 @@ impl<'a> SliceRead<'a> {
     fn next5_no_error(&mut self) -> Option<[u8; 5]> {
         let mut buf = [0; 5];
         for ch in &mut buf {
-            *ch = match self.next_no_error() {
-                Some(ch) => ch,
-                None => return None,
-            };
+            *ch = self.next_no_error()?;
         }
         Some(buf)
     }
@@ -68,9 +76,12 @@ impl<'a> SliceRead<'a> {
         Ok(buf)
     }
 
-    fn parse_number(&mut self) -> Result<(), ReadError> {
+    fn parse_number(&mut self, scan: &mut NumberScan) -> Result<(), ReadError> {
         match self.peek_no_error() {
-            Some(b'-') => self.discard(),
+            Some(b'-') => {
+                self.discard();
+                scan.push(NumberPart::Integer, b'-', self.position())?;
+            }
             Some(b'0'..=b'9') => (),
             Some(_) => return Err(ReadError::Bug {
                 msg:
@@ -91,6 +102,7 @@ impl<'a> SliceRead<'a> {
                 })
             }
         };
+        scan.push(NumberPart::Integer, first, self.position())?;
 
         let second = self.peek_no_error();
         if second.is_none() {
@@ -103,9 +115,12 @@ impl<'a> SliceRead<'a> {
 
         loop {
             match self.peek_no_error() {
-                Some(b'0'..=b'9') => self.discard(),
-                Some(b'.') => return self.parse_float(),
-                Some(b'e') | Some(b'E') => return self.parse_exponent(),
+                Some(n @ b'0'..=b'9') => {
+                    self.discard();
+                    scan.push(NumberPart::Integer, n, self.position())?;
+                }
+                Some(b'.') => return self.parse_float(scan),
+                Some(b'e') | Some(b'E') => return self.parse_exponent(scan),
                 _ => break,
             }
         }
@@ -113,24 +128,31 @@ impl<'a> SliceRead<'a> {
         Ok(())
     }
 
-    fn parse_float(&mut self) -> Result<(), ReadError> {
+    fn parse_float(&mut self, scan: &mut NumberScan) -> Result<(), ReadError> {
         if self.next_no_error() != Some(b'.') {
             return Err(ReadError::Bug {
                 msg: "SliceRead.parse_float: assume the first character is a period".to_string(),
                 position: self.position(),
             });
         }
+        scan.push(NumberPart::Fraction, b'.', self.position())?;
 
         match self.peek_no_error() {
-            Some(b'0'..=b'9') => self.discard(),
+            Some(n @ b'0'..=b'9') => {
+                self.discard();
+                scan.push(NumberPart::Fraction, n, self.position())?;
+            }
             Some(_) => return Err(ReadError::NoNumberCharactersAfterFraction(self.position())),
             None => return Err(ReadError::UnexpectedEndOfInput(self.position())),
         }
 
         loop {
             match self.peek_no_error() {
-                Some(b'0'..=b'9') => self.discard(),
-                Some(b'e') | Some(b'E') => return self.parse_exponent(),
+                Some(n @ b'0'..=b'9') => {
+                    self.discard();
+                    scan.push(NumberPart::Fraction, n, self.position())?;
+                }
+                Some(b'e') | Some(b'E') => return self.parse_exponent(scan),
                 _ => break,
             }
         }
@@ -138,17 +160,25 @@ impl<'a> SliceRead<'a> {
         Ok(())
     }
 
-    fn parse_exponent(&mut self) -> Result<(), ReadError> {
-        if !matches!(self.next_no_error(), Some(b'e') | Some(b'E')) {
-            return Err(ReadError::Bug {
-                msg: "SliceRead.parse_exponent: assume the first character is an exponent"
-                    .to_string(),
-                position: self.position(),
-            });
+    fn parse_exponent(&mut self, scan: &mut NumberScan) -> Result<(), ReadError> {
+        match self.next_no_error() {
+            Some(e @ b'e') | Some(e @ b'E') => {
+                scan.push(NumberPart::Exponent, e, self.position())?
+            }
+            _ => {
+                return Err(ReadError::Bug {
+                    msg: "SliceRead.parse_exponent: assume the first character is an exponent"
+                        .to_string(),
+                    position: self.position(),
+                })
+            }
         }
 
         match self.peek_no_error() {
-            Some(b'-') | Some(b'+') => self.discard(),
+            Some(sign @ b'-') | Some(sign @ b'+') => {
+                self.discard();
+                scan.push(NumberPart::Exponent, sign, self.position())?;
+            }
             Some(b'0'..=b'9') => (),
             Some(_) => return Err(ReadError::NoNumberCharactersAfterExponent(self.position())),
             None => return Err(ReadError::UnexpectedEndOfInput(self.position())),
@@ -160,76 +190,97 @@ impl<'a> SliceRead<'a> {
             None => return Err(ReadError::UnexpectedEndOfInput(self.position())),
         }
 
-        while let Some(b'0'..=b'9') = self.peek_no_error() {
+        while let Some(n @ b'0'..=b'9') = self.peek_no_error() {
             self.discard();
+            scan.push(NumberPart::Exponent, n, self.position())?;
         }
 
         Ok(())
     }
 
-    fn parse_escape_sequence(&mut self, buf: &mut Vec<u8>) -> Result<(), ReadError> {
-        // assume that the previous character is b'\'
-        let mut simple_escape = true;
+    fn check_buffer(&self, buf: &[u8], limits: TokenLimits) -> Result<(), ReadError> {
+        if buf.len() > limits.max_buffer_bytes {
+            return Err(ReadError::BufferLimitExceeded(self.position()));
+        }
+        Ok(())
+    }
+
+    fn parse_escape_sequence(
+        &mut self,
+        buf: &mut Vec<u8>,
+        policy: SurrogatePolicy,
+        summary: &mut StringSummary,
+    ) -> Result<(), ReadError> {
+        // assume that the previous character is b'\\'
+        summary.escapes += 1;
+        let mut escape_start = before_backslash(self.position());
 
         match self.next_no_error() {
-            Some(b'"') => buf.push(b'"'),
-            Some(b'\\') => buf.push(b'\\'),
-            Some(b'/') => buf.push(b'/'),
-            Some(b'b') => buf.push(b'\x08'),
-            Some(b'f') => buf.push(b'\x0C'),
-            Some(b'n') => buf.push(b'\n'),
-            Some(b'r') => buf.push(b'\r'),
-            Some(b't') => buf.push(b'\t'),
-            Some(b'u') => simple_escape = false,
-            Some(_) => return Err(ReadError::InvalidEscapeSequence(self.position())),
+            Some(b'u') => (),
+            Some(escape) => match decode_simple_escape(escape) {
+                Some(ch) => {
+                    buf.push(ch);
+                    return Ok(());
+                }
+                None => return Err(ReadError::InvalidEscapeSequence(self.position())),
+            },
             None => return Err(ReadError::UnexpectedEndOfInput(self.position())),
         };
 
-        if simple_escape {
-            return Ok(());
-        }
-
-        let hex = decode_hex_sequence(&self.next4_hex()?);
-        let ch = match hex {
-            _n @ 0xDC00..=0xDFFF => {
-                return Err(ReadError::InvalidEscapeSequence(self.position()));
-            }
-            n @ 0xD800..=0xDBFF => {
-                let high = n;
-                if self.next_no_error() != Some(b'\\') {
-                    return Err(ReadError::InvalidEscapeSequence(self.position()));
-                }
-                if self.next_no_error() != Some(b'u') {
-                    return Err(ReadError::InvalidEscapeSequence(self.position()));
-                }
-                let low = decode_hex_sequence(&self.next4_hex()?);
-                if !matches!(low, 0xDC00..=0xDFFF) {
-                    return Err(ReadError::InvalidEscapeSequence(self.position()));
+        let mut hex = decode_hex_sequence(&self.next4_hex()?);
+        loop {
+            let ch = match hex {
+                0xDC00..=0xDFFF => {
+                    let surrogate = LoneSurrogate::new(SurrogateKind::Low, hex, escape_start);
+                    let err = ReadError::LoneLowSurrogate(self.position());
+                    return handle_lone_surrogate(policy, buf, summary, surrogate, err);
                 }
+                0xD800..=0xDBFF => {
+                    let high = hex;
+                    let surrogate = LoneSurrogate::new(SurrogateKind::High, hex, escape_start);
+                    let lone = ReadError::LoneHighSurrogate(self.position());
+                    if self.peek_no_error() != Some(b'\\') {
+                        return handle_lone_surrogate(policy, buf, summary, surrogate, lone);
+                    }
+                    escape_start = self.position();
+                    self.discard();
+                    summary.escapes += 1;
+
+                    match self.next_no_error() {
+                        Some(b'u') => (),
+                        Some(escape) => match decode_simple_escape(escape) {
+                            Some(ch) => {
+                                handle_lone_surrogate(policy, buf, summary, surrogate, lone)?;
+                                buf.push(ch);
+                                return Ok(());
+                            }
+                            None => return Err(ReadError::InvalidEscapeSequence(self.position())),
+                        },
+                        None => return Err(ReadError::UnexpectedEndOfInput(self.position())),
+                    }
 
-                let high = ((high & 0x03FF) << 10) as u32;
-                let low = (low & 0x03FF) as u32;
-                let codepoint = 0x10000u32 + high + low;
+                    let low = decode_hex_sequence(&self.next4_hex()?);
+                    if !matches!(low, 0xDC00..=0xDFFF) {
+                        // the next escape sequence is decoded on its own
+                        handle_lone_surrogate(policy, buf, summary, surrogate, lone)?;
+                        hex = low;
+                        continue;
+                    }
 
-                match std::char::from_u32(codepoint) {
+                    decode_surrogate_pair(high, low)
+                }
+                n => match std::char::from_u32(n as u32) {
                     Some(ch) => ch,
                     None => return Err(ReadError::Bug {
                         msg: "SliceRead.parse_escape_sequence: assume the codepoint is a valid Unicode codepoint".to_string(),
                         position: self.position(),
                     }),
                 }
-            }
-            n => match std::char::from_u32(n as u32) {
-                Some(ch) => ch,
-                None => return Err(ReadError::Bug {
-                    msg: "SliceRead.parse_escape_sequence: assume the codepoint is a valid Unicode codepoint".to_string(),
-                    position: self.position(),
-                }),
-            }
-        };
+            };
 
-        buf.extend_from_slice(ch.encode_utf8(&mut [0u8; 4]).as_bytes());
-        Ok(())
+            buf.extend_from_slice(ch.encode_utf8(&mut [0u8; 4]).as_bytes());
+            return Ok(());
+        }
     }
 }
 
@@ -238,6 +289,22 @@ impl<'a> Read for SliceRead<'a> {
         self.iter.position()
     }
 
+    fn set_max_payload_bytes(&mut self, limit: usize) {
+        self.iter.truncate(limit.saturating_add(1));
+    }
+
+    fn consumed_slice(&self, start: usize, end: usize) -> Option<&[u8]> {
+        if start < self.base || start > end || end > self.iter.position().offset {
+            return None;
+        }
+        Some(&self.slice[start - self.base..end - self.base])
+    }
+
+    fn resume_at(&mut self, position: Position) {
+        self.base = position.offset;
+        self.iter.set_position(position);
+    }
+
     fn peek(&mut self) -> Result<Option<u8>, ReadError> {
         Ok(self.peek_no_error())
     }
@@ -267,11 +334,16 @@ impl<'a> Read for SliceRead<'a> {
         Ok(())
     }
 
-    fn next_number(&mut self) -> Result<(), ReadError> {
-        self.parse_number()
+    fn next_number(&mut self, buf: &mut Vec<u8>, limits: TokenLimits) -> Result<(), ReadError> {
+        self.parse_number(&mut NumberScan::new(buf, limits))
     }
 
-    fn next_likely_string(&mut self, buf: &mut Vec<u8>) -> Result<(), ReadError> {
+    fn next_likely_string(
+        &mut self,
+        buf: &mut Vec<u8>,
+        policy: SurrogatePolicy,
+        limits: TokenLimits,
+    ) -> Result<StringSummary, ReadError> {
         if self.next_no_error() != Some(b'"') {
             return Err(ReadError::Bug {
                 msg: "SliceRead.next_likely_string: assume the first character is a double quote"
@@ -280,17 +352,20 @@ impl<'a> Read for SliceRead<'a> {
             });
         }
 
+        let start = self.position().offset;
+        let mut summary = StringSummary::default();
         while let Some(byte) = self.next_no_error() {
+            self.check_buffer(buf, limits)?;
             if !NEED_ESCAPE[byte as usize] {
                 buf.push(byte);
-                continue;
-            }
-
-            match byte {
-                b'"' => return Ok(()),
-                b'\\' => self.parse_escape_sequence(buf)?,
-                _ => return Err(ReadError::ControlCharacterInString(self.position())),
+            } else {
+                match byte {
+                    b'"' => return Ok(summary),
+                    b'\\' => self.parse_escape_sequence(buf, policy, &mut summary)?,
+                    _ => return Err(ReadError::ControlCharacterInString(self.position())),
+                }
             }
+            check_string(self.position(), start, &summary, limits)?;
         }
 
         Err(ReadError::UnclosedString(self.position()))
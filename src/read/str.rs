@@ -1,5 +1,5 @@
 use super::slice::SliceRead;
-use super::{Position, Read, ReadError};
+use super::{Position, Read, ReadError, StringSummary, SurrogatePolicy, TokenLimits};
 
 /// A reader for strings which implements the [`Read`] trait.
 pub struct StrRead<'a> {
@@ -20,6 +20,18 @@ impl Read for StrRead<'_> {
         self.slice_read.position()
     }
 
+    fn set_max_payload_bytes(&mut self, limit: usize) {
+        self.slice_read.set_max_payload_bytes(limit)
+    }
+
+    fn consumed_slice(&self, start: usize, end: usize) -> Option<&[u8]> {
+        self.slice_read.consumed_slice(start, end)
+    }
+
+    fn resume_at(&mut self, position: Position) {
+        self.slice_read.resume_at(position)
+    }
+
     fn peek(&mut self) -> Result<Option<u8>, ReadError> {
         self.slice_read.peek()
     }
@@ -40,11 +52,16 @@ impl Read for StrRead<'_> {
         self.slice_read.skip_whitespace()
     }
 
-    fn next_number(&mut self) -> Result<(), ReadError> {
-        self.slice_read.next_number()
+    fn next_number(&mut self, buf: &mut Vec<u8>, limits: TokenLimits) -> Result<(), ReadError> {
+        self.slice_read.next_number(buf, limits)
     }
 
-    fn next_likely_string(&mut self, buf: &mut Vec<u8>) -> Result<(), ReadError> {
-        self.slice_read.next_likely_string(buf)
+    fn next_likely_string(
+        &mut self,
+        buf: &mut Vec<u8>,
+        policy: SurrogatePolicy,
+        limits: TokenLimits,
+    ) -> Result<StringSummary, ReadError> {
+        self.slice_read.next_likely_string(buf, policy, limits)
     }
 }
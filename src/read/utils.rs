@@ -94,7 +94,38 @@ pub fn decode_hex_sequence(hexes: &[u8; 4]) -> u16 {
     value
 }
 
+/// Decode the character after the backslash of a simple escape sequence, such as `n` of `\n`,
+/// returns `None` if it is not a simple escape sequence.
+pub fn decode_simple_escape(escape: u8) -> Option<u8> {
+    match escape {
+        b'"' => Some(b'"'),
+        b'\\' => Some(b'\\'),
+        b'/' => Some(b'/'),
+        b'b' => Some(b'\x08'),
+        b'f' => Some(b'\x0C'),
+        b'n' => Some(b'\n'),
+        b'r' => Some(b'\r'),
+        b't' => Some(b'\t'),
+        _ => None,
+    }
+}
+
+/// Decode a surrogate pair into the character it represents.
+///
+/// # Panics
+///
+/// This function panics if the input is not a high surrogate followed by a low surrogate.
+pub fn decode_surrogate_pair(high: u16, low: u16) -> char {
+    let high = ((high & 0x03FF) as u32) << 10;
+    let low = (low & 0x03FF) as u32;
+    let codepoint = 0x10000u32 + high + low;
+
+    // unwrap is safe because a surrogate pair is always in 0x10000..=0x10FFFF
+    std::char::from_u32(codepoint).unwrap()
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Represents a position where is the validator currently at.
 pub struct Position {
     /// Line number, starting from `1``
@@ -169,6 +200,22 @@ impl<'a, I: Iterator<Item = &'a u8>> LineColumnIterator<'a, I> {
     pub fn position(&self) -> Position {
         self.position
     }
+
+    /// Treat the current position as the given one.
+    pub fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+}
+
+impl<'a> LineColumnIterator<'a, std::slice::Iter<'a, u8>> {
+    /// Truncate the underlying slice, so that at most `len` bytes
+    /// could be consumed from the beginning of the entire input.
+    pub fn truncate(&mut self, len: usize) {
+        let consumed = self.position.offset + self.ch.is_some() as usize;
+        let remaining = self.iter.as_slice();
+        let keep = len.saturating_sub(consumed).min(remaining.len());
+        self.iter = remaining[..keep].iter();
+    }
 }
 
 impl<'a, I: Iterator<Item = &'a u8>> Iterator for LineColumnIterator<'a, I> {
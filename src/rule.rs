@@ -0,0 +1,146 @@
+//! Custom validation rules, which are registered by [`crate::Validator::with_rule`].
+//!
+//! The built-in constraints cover the generic threats,
+//! and the [`Rule`] trait is used to enforce the domain specific rules
+//! while validating the JSON payload without a second parser.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use json_threat_protection::{self as jtp, rule::{Context, Rule, RuleError}};
+//!
+//! /// Rejects the MongoDB operators in object entry names.
+//! struct NoMongoOperators;
+//!
+//! impl Rule for NoMongoOperators {
+//!     fn on_key(&mut self, _ctx: &Context<'_>, key: &str) -> Result<(), RuleError> {
+//!         match key {
+//!             "$where" | "$function" | "$accumulator" => Err(format!("forbidden key {}", key).into()),
+//!             _ => Ok(()),
+//!         }
+//!     }
+//! }
+//!
+//! let err = jtp::from_str(r#"{"filter": {"$where": "sleep(1000)"}}"#)
+//!     .with_rule(NoMongoOperators)
+//!     .validate()
+//!     .unwrap_err();
+//! assert_eq!(err.path(), Some("/filter/$where"));
+//! ```
+
+use crate::read::LoneSurrogate;
+use crate::validator::{pointer, State};
+
+/// Error returned by the callbacks of [`Rule`],
+/// it is wrapped in [`crate::Error::Custom`] by the validator.
+pub type RuleError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A custom validation rule.
+///
+/// Every callback is invoked after the built-in constraints of the same token are checked,
+/// and returns `Ok(())` by default, so only the interested callbacks need to be implemented.
+/// Returning an `Err` aborts the validation with [`crate::Error::Custom`].
+pub trait Rule: Send {
+    /// Called for each object entry name.
+    fn on_key(&mut self, _ctx: &Context<'_>, _key: &str) -> Result<(), RuleError> {
+        Ok(())
+    }
+
+    /// Called for each string value, the string has been unescaped.
+    fn on_string(&mut self, _ctx: &Context<'_>, _value: &str) -> Result<(), RuleError> {
+        Ok(())
+    }
+
+    /// Called for each number value with the literal as it is in the payload.
+    fn on_number(&mut self, _ctx: &Context<'_>, _literal: &str) -> Result<(), RuleError> {
+        Ok(())
+    }
+
+    /// Called for each lone surrogate in a string or an object entry name
+    /// with [`crate::SurrogatePolicy::Report`], before [`Rule::on_key`] or [`Rule::on_string`] of the string.
+    fn on_lone_surrogate(
+        &mut self,
+        _ctx: &Context<'_>,
+        _surrogate: &LoneSurrogate,
+    ) -> Result<(), RuleError> {
+        Ok(())
+    }
+
+    /// Called for each `true` or `false` value.
+    fn on_bool(&mut self, _ctx: &Context<'_>, _value: bool) -> Result<(), RuleError> {
+        Ok(())
+    }
+
+    /// Called for each `null` value.
+    fn on_null(&mut self, _ctx: &Context<'_>) -> Result<(), RuleError> {
+        Ok(())
+    }
+
+    /// Called at the start of each object.
+    fn on_start_object(&mut self, _ctx: &Context<'_>) -> Result<(), RuleError> {
+        Ok(())
+    }
+
+    /// Called at the end of each object.
+    fn on_end_object(&mut self, _ctx: &Context<'_>) -> Result<(), RuleError> {
+        Ok(())
+    }
+
+    /// Called at the start of each array.
+    fn on_start_array(&mut self, _ctx: &Context<'_>) -> Result<(), RuleError> {
+        Ok(())
+    }
+
+    /// Called at the end of each array.
+    fn on_end_array(&mut self, _ctx: &Context<'_>) -> Result<(), RuleError> {
+        Ok(())
+    }
+}
+
+/// Where the validator currently is, passed to the callbacks of [`Rule`].
+///
+/// For [`Rule::on_key`], it describes the value that follows the key,
+/// and for the start and end of a container, it describes the container itself.
+pub struct Context<'a> {
+    states: &'a [State],
+    entries: &'a [usize],
+    names: &'a [Option<String>],
+    depth: usize,
+}
+
+impl<'a> Context<'a> {
+    pub(crate) fn new(
+        states: &'a [State],
+        entries: &'a [usize],
+        names: &'a [Option<String>],
+        depth: usize,
+    ) -> Self {
+        Context {
+            states,
+            entries,
+            names,
+            depth,
+        }
+    }
+
+    /// Returns the JSON Pointer (RFC 6901) of the current value,
+    /// which is built on demand.
+    pub fn path(&self) -> String {
+        pointer(self.states, self.entries, self.names)
+    }
+
+    /// Returns the number of arrays and objects enclosing the current value,
+    /// `0` for the root value.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the name of the object entry of the current value,
+    /// or `None` if the value is an array entry or the root value.
+    pub fn key(&self) -> Option<&str> {
+        match self.names.last() {
+            Some(Some(name)) => Some(name),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+/// Checks of the code points in string values.
+///
+/// Some code points are valid in JSON strings but dangerous once displayed or stored,
+/// such as the bidirectional overrides used by the
+/// [Trojan Source](https://trojansource.codes/) attacks,
+/// which make the text rendered differently from its logical order.
+/// Each check could be enabled separately,
+/// and the code points are checked after the escape sequences are decoded,
+/// so `"\u202e"` is rejected the same as the literal character.
+///
+/// The default checks reject nothing.
+///
+/// # Examples
+///
+/// ```rust
+/// use json_threat_protection::{self as jtp, UnicodeChecks};
+///
+/// let checks = UnicodeChecks {
+///     reject_bidi_controls: true,
+///     reject_nul: true,
+///     ..Default::default()
+/// };
+///
+/// assert!(jtp::from_str(r#"["abc"]"#).with_unicode_checks(checks).validate().is_ok());
+/// assert!(jtp::from_str(r#"["abc\u202edcb"]"#).with_unicode_checks(checks).validate().is_err());
+/// assert!(jtp::from_str(r#"["abc\u0000"]"#).with_unicode_checks(checks).validate().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct UnicodeChecks {
+    /// Whether to reject the bidirectional embeddings, overrides and isolates,
+    /// which are U+202A to U+202E and U+2066 to U+2069
+    pub reject_bidi_controls: bool,
+
+    /// Whether to reject the zero-width characters,
+    /// which are U+200B to U+200D, U+2060 and U+FEFF
+    pub reject_zero_width: bool,
+
+    /// Whether to reject the noncharacters,
+    /// which are U+FDD0 to U+FDEF and the last two code points of each plane
+    pub reject_noncharacters: bool,
+
+    /// Whether to reject the private-use code points,
+    /// which are U+E000 to U+F8FF, U+F0000 to U+FFFFD and U+100000 to U+10FFFD
+    pub reject_private_use: bool,
+
+    /// Whether to reject U+0000
+    pub reject_nul: bool,
+}
+
+impl UnicodeChecks {
+    /// Enables all the checks.
+    pub fn all() -> Self {
+        UnicodeChecks {
+            reject_bidi_controls: true,
+            reject_zero_width: true,
+            reject_noncharacters: true,
+            reject_private_use: true,
+            reject_nul: true,
+        }
+    }
+
+    /// Finds the first character of the decoded string rejected by the checks.
+    pub(crate) fn find_rejected(&self, str: &str) -> Option<char> {
+        if *self == UnicodeChecks::default() {
+            return None;
+        }
+
+        // All the rejected code points are either NUL or outside of ASCII.
+        if !self.reject_nul && str.is_ascii() {
+            return None;
+        }
+
+        str.chars().find(|&c| self.rejects(c))
+    }
+
+    fn rejects(&self, c: char) -> bool {
+        let code_point = c as u32;
+
+        (self.reject_bidi_controls && matches!(code_point, 0x202A..=0x202E | 0x2066..=0x2069))
+            || (self.reject_zero_width && matches!(code_point, 0x200B..=0x200D | 0x2060 | 0xFEFF))
+            || (self.reject_noncharacters
+                && (matches!(code_point, 0xFDD0..=0xFDEF) || code_point & 0xFFFE == 0xFFFE))
+            || (self.reject_private_use
+                && matches!(code_point, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD))
+            || (self.reject_nul && code_point == 0)
+    }
+}
@@ -1,21 +1,78 @@
 use thiserror::Error;
 
+use crate::charset::KeyCharset;
+use crate::checkpoint::Checkpoint;
+use crate::config::ValidatorConfig;
+use crate::key_policy::KeyPolicy;
+use crate::keys::{decode_key, table_bytes, Fingerprinter, KeySet, KeySpan};
+use crate::length::LengthUnit;
 use crate::lexer::Lexer;
 use crate::lexer::LexerError;
 use crate::lexer::Token;
-use crate::read::{Position, Read};
-use std::collections::HashSet;
+use crate::normalization::KeyNormalization;
+use crate::number::{NumberParts, NumberPolicy};
+use crate::prototype::PrototypePollutionPolicy;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use crate::read::{AsyncIoRead, AsyncSource};
+use crate::read::{FeedRead, IoRead, Position, Read, ReadError, SurrogatePolicy};
+use crate::rule::{Context, Rule, RuleError};
+use crate::unicode::UnicodeChecks;
+use crate::NO_LIMIT;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// Bytes of the strings kept in the errors collected by [`Validator::validate_all`].
+const COLLECTED_EXCERPT_BYTES: usize = 64;
+
+/// Bytes of the paths kept in the errors collected by [`Validator::validate_all`].
+const COLLECTED_PATH_BYTES: usize = 256;
+
+/// The longest prefix of the `str` within the `limit` bytes.
+fn prefix(str: &str, limit: usize) -> &str {
+    if str.len() <= limit {
+        return str;
+    }
+
+    let mut end = limit;
+    while !str.is_char_boundary(end) {
+        end -= 1;
+    }
+    &str[..end]
+}
 
 #[allow(unused_macros)]
 macro_rules! allow_duplicate_object_entry_name {
     ($self:ident) => {
-        $self.allow_duplicate_object_entry_name
+        $self.config.allow_duplicate_object_entry_name
     };
 }
 
 macro_rules! disallow_duplicate_object_entry_name {
     ($self:ident) => {{
-        !$self.allow_duplicate_object_entry_name
+        !$self.config.allow_duplicate_object_entry_name
+    }};
+}
+
+/// Reports a constraint violation,
+/// which is collected if the validator is collecting errors,
+/// otherwise it is returned immediately.
+macro_rules! report {
+    ($self:ident, $err:expr) => {{
+        let err = $err;
+        if $self.collect_errors && $self.errors.len() + 1 < $self.config.max_errors {
+            let position = $self.lexer.position();
+            let err = locate(err, position, || {
+                let mut path =
+                    $self
+                        .pointer_cache
+                        .pointer(&$self.states, &$self.entires, &$self.names);
+                path.truncate(prefix(&path, COLLECTED_PATH_BYTES).len());
+                path
+            });
+            $self.errors.push(err);
+        } else {
+            return Err(err);
+        }
     }};
 }
 
@@ -23,10 +80,13 @@ macro_rules! try_inc_depth {
     ($self:ident, $position:ident) => {
         $self.cur_depth += 1;
 
-        if $self.cur_depth > $self.max_depth {
+        // The depth violation is always fatal even if the errors are collected,
+        // otherwise the stacks would keep growing with the nesting beyond the limit.
+        if $self.cur_depth > $self.config.max_depth {
             return Err(ValidatorError::MaxDepthExceeded {
                 position: $position,
-                limit: $self.max_depth,
+                path: String::new(),
+                limit: $self.config.max_depth,
             });
         }
     };
@@ -39,36 +99,189 @@ macro_rules! try_dec_depth {
         }
 
         $self.cur_depth -= 1;
+    };
+}
 
-        if $self.cur_depth > $self.max_depth {
-            return Err(ValidatorError::MaxDepthExceeded {
+macro_rules! try_check_payload_size {
+    ($self:ident, $position:ident) => {
+        if $self.lexer.position().offset > $self.config.max_payload_bytes {
+            return Err(ValidatorError::MaxPayloadSizeExceeded {
+                position: $position,
+                path: String::new(),
+                limit: $self.config.max_payload_bytes,
+            });
+        }
+    };
+}
+
+macro_rules! try_check_validator_memory {
+    ($self:ident, $position:ident) => {
+        if $self.config.max_validator_memory != NO_LIMIT
+            && $self.memory() > $self.config.max_validator_memory
+        {
+            return Err(ValidatorError::MaxValidatorMemoryExceeded {
                 position: $position,
-                limit: $self.max_depth,
+                path: String::new(),
+                limit: $self.config.max_validator_memory,
             });
         }
     };
 }
 
+macro_rules! try_check_raw_string {
+    ($self:ident, $position:ident) => {
+        // The raw length and the escape sequences are limited by the lexer while the string is read.
+        if $self.config.surrogate_policy == SurrogatePolicy::Report {
+            for surrogate in $self.lexer.take_token_lone_surrogates() {
+                try_apply_rules!($self, $position, on_lone_surrogate, &surrogate);
+            }
+        }
+    };
+}
+
+macro_rules! try_check_number {
+    ($self:ident, $number:ident, $position:ident) => {
+        // The length and the digits are limited by the lexer while the number is read,
+        // and the literal is only buffered once the policy needs it.
+        if $self.config.number_policy != NumberPolicy::ArbitraryPrecision
+            && !$self
+                .config
+                .number_policy
+                .accepts($number, &NumberParts::new($number))
+        {
+            report!(
+                $self,
+                ValidatorError::NumberPolicyViolation {
+                    position: $position,
+                    path: String::new(),
+                    policy: $self.config.number_policy,
+                }
+            );
+        }
+    };
+}
+
+/// Invokes the callback of every custom rule,
+/// the path and the depth are taken from the current state of the validator.
+macro_rules! try_apply_rules {
+    ($self:ident, $position:ident, $callback:ident $(, $arg:expr)*) => {
+        if !$self.rules.is_empty() {
+            let mut failures: Vec<RuleError> = Vec::new();
+            let context = Context::new(&$self.states, &$self.entires, &$self.names, $self.cur_depth);
+            for rule in $self.rules.iter_mut() {
+                if let Err(source) = rule.$callback(&context $(, $arg)*) {
+                    failures.push(source);
+                    if !$self.collect_errors {
+                        break;
+                    }
+                }
+            }
+
+            for source in failures {
+                report!(
+                    $self,
+                    ValidatorError::Custom {
+                        position: $position,
+                        path: String::new(),
+                        source,
+                    }
+                );
+            }
+        }
+    };
+}
+
+/// Normalizes the object entry name before detecting duplicates.
+#[cfg(feature = "unicode-normalization")]
+fn normalize_key<'a>(config: &ValidatorConfig, key: &'a str) -> Cow<'a, str> {
+    config.key_normalization.normalize(key)
+}
+
+#[cfg(not(feature = "unicode-normalization"))]
+fn normalize_key<'a>(_config: &ValidatorConfig, key: &'a str) -> Cow<'a, str> {
+    Cow::Borrowed(key)
+}
+
+/// The spelling of the object entry name kept for the duplicate detection,
+/// which is only kept once the names are normalized,
+/// otherwise a duplicate is spelled the same as the earlier name.
+#[cfg(feature = "unicode-normalization")]
+fn kept_spelling<'a>(config: &ValidatorConfig, key: &'a str) -> Option<&'a str> {
+    (config.key_normalization != KeyNormalization::default()).then_some(key)
+}
+
+#[cfg(not(feature = "unicode-normalization"))]
+fn kept_spelling<'a>(_config: &ValidatorConfig, _key: &'a str) -> Option<&'a str> {
+    None
+}
+
+/// Rejects the options of the features which are not enabled,
+/// rather than silently ignoring them.
+#[allow(unused_variables)]
+fn check_config(config: &ValidatorConfig) -> Result<(), ValidatorError> {
+    #[cfg(not(feature = "unicode-normalization"))]
+    if config.key_normalization != KeyNormalization::default() {
+        return Err(ValidatorError::FeatureNotEnabled {
+            option: "key_normalization",
+            feature: "unicode-normalization",
+        });
+    }
+
+    #[cfg(not(feature = "unicode-segmentation"))]
+    if config.length_unit == LengthUnit::Graphemes {
+        return Err(ValidatorError::FeatureNotEnabled {
+            option: "length_unit",
+            feature: "unicode-segmentation",
+        });
+    }
+
+    #[cfg(not(feature = "regex"))]
+    if config
+        .key_policies
+        .iter()
+        .flat_map(|policy| &policy.patterns)
+        .any(|pattern| matches!(pattern, crate::key_policy::KeyPattern::Regex(_)))
+    {
+        return Err(ValidatorError::FeatureNotEnabled {
+            option: "key_policies",
+            feature: "regex",
+        });
+    }
+
+    Ok(())
+}
+
 macro_rules! try_active_array {
     ($self:ident, $position:ident) => {
         $self.entires.push(0);
+        $self.names.push(None);
+        $self.pointer_cache.invalidate($self.entires.len() - 1);
         try_inc_depth!($self, $position);
     };
 }
 
 macro_rules! try_add_array_entry {
     ($self:ident, $position:ident) => {
+        $self
+            .pointer_cache
+            .invalidate($self.entires.len().saturating_sub(1));
         let entries = $self
             .entires
             .last_mut()
             .ok_or(ValidatorError::InvalidJSON($position))?;
         *entries += 1;
 
-        if *entries > $self.max_array_entries {
-            return Err(ValidatorError::MaxArrayEntriesExceeded {
-                position: $position,
-                limit: $self.max_array_entries,
-            });
+        // Only report once the limit is exceeded for the first time,
+        // instead of reporting every entry beyond the limit.
+        if *entries - 1 == $self.config.max_array_entries {
+            report!(
+                $self,
+                ValidatorError::MaxArrayEntriesExceeded {
+                    position: $position,
+                    path: String::new(),
+                    limit: $self.config.max_array_entries,
+                }
+            );
         }
     };
 }
@@ -79,6 +292,10 @@ macro_rules! try_finalize_array {
             .entires
             .pop()
             .ok_or(ValidatorError::InvalidJSON($position))?;
+        if let Some(Some(name)) = $self.names.pop() {
+            $self.name_bytes -= name.capacity();
+        }
+        $self.pointer_cache.invalidate($self.entires.len());
         try_dec_depth!($self, $position);
         entries
     }};
@@ -87,21 +304,93 @@ macro_rules! try_finalize_array {
 macro_rules! try_active_object {
     ($self:ident, $position:ident) => {
         if disallow_duplicate_object_entry_name!($self) {
-            $self.keys.push(HashSet::with_capacity(8));
+            let keys = KeySet::with_capacity(8);
+            $self.key_table_bytes += table_bytes(&keys);
+            $self.keys.push(keys);
         }
         $self.entires.push(0);
+        $self.names.push(None);
+        $self.pointer_cache.invalidate($self.entires.len() - 1);
         try_inc_depth!($self, $position);
     };
 }
 
 macro_rules! try_add_object_key {
     ($self:ident, $key:ident, $position:ident) => {
-        if $key.len() > $self.max_object_entry_name_length {
-            return Err(ValidatorError::MaxObjectEntryNameLengthExceeded {
-                position: $position,
-                limit: $self.max_object_entry_name_length,
-                name: $key.to_string(),
-            });
+        $self
+            .pointer_cache
+            .invalidate($self.names.len().saturating_sub(1));
+        match $self.names.last_mut() {
+            Some(Some(name)) => {
+                let capacity = name.capacity();
+                name.clear();
+                name.push_str($key);
+                $self.name_bytes += name.capacity() - capacity;
+            }
+            Some(name) => {
+                let key = $key.to_string();
+                $self.name_bytes += key.capacity();
+                *name = Some(key);
+            }
+            None => return Err(ValidatorError::InvalidJSON($position)),
+        }
+
+        if $self
+            .config
+            .length_unit
+            .exceeds($key, $self.config.max_object_entry_name_length)
+        {
+            report!(
+                $self,
+                ValidatorError::MaxObjectEntryNameLengthExceeded {
+                    position: $position,
+                    path: String::new(),
+                    limit: $self.config.max_object_entry_name_length,
+                    unit: $self.config.length_unit,
+                    name: $self.excerpt($key),
+                }
+            );
+        }
+
+        if let Some(character) = $self.config.key_charset.find_forbidden($key) {
+            report!(
+                $self,
+                ValidatorError::ForbiddenCharacterInObjectEntryName {
+                    position: $position,
+                    path: String::new(),
+                    name: $self.excerpt($key),
+                    character,
+                }
+            );
+        }
+
+        if $self
+            .config
+            .prototype_pollution_policy
+            .rejects($key, $self.lexer.token_escapes() > 0)
+        {
+            report!(
+                $self,
+                ValidatorError::ForbiddenObjectEntryName {
+                    position: $position,
+                    path: String::new(),
+                    name: $self.excerpt($key),
+                }
+            );
+        } else if $self
+            .config
+            .key_policies
+            .iter()
+            .any(|policy| !policy.accepts($key, $self.cur_depth))
+        {
+            report!(
+                $self,
+                ValidatorError::ForbiddenObjectEntryName {
+                    position: $position,
+                    path: String::new(),
+                    name: $self.excerpt($key),
+                }
+            );
         }
 
         if disallow_duplicate_object_entry_name!($self) {
@@ -110,11 +399,64 @@ macro_rules! try_add_object_key {
                 .last_mut()
                 .ok_or(ValidatorError::InvalidJSON($position))?;
 
-            if !keys.insert($key.to_string()) {
-                return Err(ValidatorError::DuplicateObjectEntryName {
-                    position: $position,
-                    key: $key.to_string(),
-                });
+            let normalized = normalize_key(&$self.config, $key);
+            let fingerprint = $self.fingerprinter.fingerprint(normalized.as_bytes());
+
+            // Confirm the collision with each earlier name if its spelling is kept
+            // or the source is available.
+            let mut duplicate = None;
+            for earlier in keys.get(fingerprint) {
+                let earlier_key = match keys.spelling(earlier) {
+                    Some(spelling) => Some(spelling.to_string()),
+                    None => $self
+                        .lexer
+                        .consumed_slice(earlier.start, earlier.end)
+                        .and_then(decode_key),
+                };
+                if earlier_key.as_deref().map_or(true, |earlier| {
+                    normalize_key(&$self.config, earlier) == normalized
+                }) {
+                    duplicate = Some(earlier_key);
+                    break;
+                }
+            }
+
+            match duplicate {
+                Some(earlier_key) => {
+                    report!(
+                        $self,
+                        ValidatorError::DuplicateObjectEntryName {
+                            position: $position,
+                            path: String::new(),
+                            key: $self.excerpt($key),
+                            earlier_key: earlier_key.map(|earlier| $self.excerpt(&earlier)),
+                        }
+                    );
+                }
+                None if $self.key_bytes
+                    + KeySet::entry_bytes(kept_spelling(&$self.config, $key))
+                    > $self.config.max_duplicate_detection_bytes =>
+                {
+                    report!(
+                        $self,
+                        ValidatorError::MaxDuplicateDetectionBytesExceeded {
+                            position: $position,
+                            path: String::new(),
+                            limit: $self.config.max_duplicate_detection_bytes,
+                        }
+                    );
+                }
+                None => {
+                    let span = KeySpan {
+                        start: $self.lexer.token_start().offset,
+                        end: $self.lexer.position().offset,
+                    };
+                    let spelling = kept_spelling(&$self.config, $key);
+                    let table = table_bytes(keys);
+                    keys.insert(fingerprint, span, spelling);
+                    $self.key_bytes += KeySet::entry_bytes(spelling);
+                    $self.key_table_bytes += table_bytes(keys) - table;
+                }
             }
         }
     };
@@ -128,11 +470,31 @@ macro_rules! try_add_object_value {
             .ok_or(ValidatorError::InvalidJSON($position))?;
         *entries += 1;
 
-        if *entries > $self.max_object_entries {
-            return Err(ValidatorError::MaxObjectEntriesExceeded {
-                position: $position,
-                limit: $self.max_object_entries,
-            });
+        // Only report once the limit is exceeded for the first time,
+        // instead of reporting every entry beyond the limit.
+        if *entries - 1 == $self.config.max_object_entries {
+            report!(
+                $self,
+                ValidatorError::MaxObjectEntriesExceeded {
+                    position: $position,
+                    path: String::new(),
+                    limit: $self.config.max_object_entries,
+                }
+            );
+        }
+    };
+}
+
+macro_rules! try_add_entry {
+    ($self:ident, $position:ident) => {
+        match $self.states.last() {
+            Some(State::ProcessingObject) => {
+                try_add_object_value!($self, $position);
+            }
+            Some(State::ProcessingArray) => {
+                try_add_array_entry!($self, $position);
+            }
+            _ => (),
         }
     };
 }
@@ -140,22 +502,29 @@ macro_rules! try_add_object_value {
 macro_rules! try_finalize_object {
     ($self:ident, $position:ident) => {{
         if disallow_duplicate_object_entry_name!($self) {
-            $self
+            let keys = $self
                 .keys
                 .pop()
                 .ok_or(ValidatorError::InvalidJSON($position))?;
+            $self.key_bytes -= keys.retained_bytes();
+            $self.key_table_bytes -= table_bytes(&keys);
         }
         let entries = $self
             .entires
             .pop()
             .ok_or(ValidatorError::InvalidJSON($position))?;
+        if let Some(Some(name)) = $self.names.pop() {
+            $self.name_bytes -= name.capacity();
+        }
+        $self.pointer_cache.invalidate($self.entires.len());
         try_dec_depth!($self, $position);
         entries
     }};
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum State {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum State {
     /// Validator is expecting an optional object entry
     ProcessingObject,
 
@@ -181,8 +550,35 @@ enum State {
     OptionalElement,
 }
 
+/// Kind of the event produced by a token, see [`crate::event::Event`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EventKind {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    Key,
+    String,
+    Number,
+    True,
+    False,
+    Null,
+}
+
+/// Progress of the validation fed by chunks, see [`crate::Validator::feed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// The fed bytes are validated except the partial token at the end, which needs more bytes.
+    NeedMoreInput,
+
+    /// A complete JSON value has been validated,
+    /// only whitespace is allowed until the end of input.
+    Complete,
+}
+
 #[derive(Error, Debug)]
 /// Error occurred during JSON validation
+#[non_exhaustive]
 pub enum ValidatorError {
     /// Error occurred during lexing
     #[error("lexer error: {0}")]
@@ -197,69 +593,326 @@ pub enum ValidatorError {
     TrailingData(Position),
 
     /// Error occurred when the maximum depth is exceeded
-    #[error("maximum depth exceeded (limit: {limit}, {position})")]
+    #[error("maximum depth exceeded (limit: {limit}, {position}, path: {path})")]
     MaxDepthExceeded {
         /// Position where the error occurred
         position: Position,
 
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
         /// Maximum depth allowed
         limit: usize,
     },
 
+    /// Error occurred when the maximum payload size is exceeded
+    #[error("maximum payload size exceeded (limit: {limit}, {position}, path: {path})")]
+    MaxPayloadSizeExceeded {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Maximum payload size allowed in bytes
+        limit: usize,
+    },
+
     /// Error occurred when the maximum string length is exceeded
-    #[error("maximum string length exceeded (limit: {limit}, {position})")]
+    #[error("maximum string length exceeded (limit: {limit} {unit}, {position}, path: {path})")]
     MaxStringLengthExceeded {
         /// Position where the error occurred
         position: Position,
 
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
         /// Maximum string length allowed
         limit: usize,
 
-        /// String that exceeds the limit
+        /// Unit of the length
+        unit: LengthUnit,
+
+        /// String that exceeds the limit,
+        /// only the first 64 bytes are kept by [`crate::Validator::validate_all`]
         str: String,
     },
 
+    /// Error occurred when the maximum number length is exceeded
+    #[error("maximum number length exceeded (limit: {limit}, {position}, path: {path})")]
+    MaxNumberLengthExceeded {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Maximum number length allowed
+        limit: usize,
+    },
+
+    /// Error occurred when the maximum number of integer digits is exceeded
+    #[error("maximum number integer digits exceeded (limit: {limit}, {position}, path: {path})")]
+    MaxNumberIntegerDigitsExceeded {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Maximum number of integer digits allowed
+        limit: usize,
+    },
+
+    /// Error occurred when the maximum number of fraction digits is exceeded
+    #[error("maximum number fraction digits exceeded (limit: {limit}, {position}, path: {path})")]
+    MaxNumberFractionDigitsExceeded {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Maximum number of fraction digits allowed
+        limit: usize,
+    },
+
+    /// Error occurred when the maximum number of exponent digits is exceeded
+    #[error("maximum number exponent digits exceeded (limit: {limit}, {position}, path: {path})")]
+    MaxNumberExponentDigitsExceeded {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Maximum number of exponent digits allowed
+        limit: usize,
+    },
+
+    /// Error occurred when the maximum absolute value of the exponent is exceeded
+    #[error("maximum exponent magnitude exceeded (limit: {limit}, {position}, path: {path})")]
+    MaxExponentMagnitudeExceeded {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Maximum absolute value of the exponent allowed
+        limit: usize,
+    },
+
+    /// Error occurred when a number is not accepted by the number policy
+    #[error("number policy violation (policy: {policy:?}, {position}, path: {path})")]
+    NumberPolicyViolation {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Number policy that rejects the number
+        policy: NumberPolicy,
+    },
+
     /// Error occurred when the maximum array entries is exceeded
-    #[error("maximum array entries exceeded (limit: {limit}, {position})")]
+    #[error("maximum array entries exceeded (limit: {limit}, {position}, path: {path})")]
     MaxArrayEntriesExceeded {
         /// Position where the error occurred
         position: Position,
 
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
         /// Maximum array entries allowed
         limit: usize,
     },
 
     /// Error occurred when the maximum object entries is exceeded
-    #[error("maximum object entries exceeded (limit: {limit}, {position})")]
+    #[error("maximum object entries exceeded (limit: {limit}, {position}, path: {path})")]
     MaxObjectEntriesExceeded {
         /// Position where the error occurred
         position: Position,
 
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
         /// Maximum object entries allowed
         limit: usize,
     },
 
+    /// Error occurred when the maximum length of strings in the source is exceeded
+    #[error("maximum string raw length exceeded (limit: {limit}, {position}, path: {path})")]
+    MaxStringRawLengthExceeded {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Maximum bytes of strings in the source allowed
+        limit: usize,
+    },
+
+    /// Error occurred when the maximum number of escape sequences in strings is exceeded
+    #[error("maximum string escapes exceeded (limit: {limit}, {position}, path: {path})")]
+    MaxStringEscapesExceeded {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Maximum number of escape sequences in strings allowed
+        limit: usize,
+    },
+
     /// Error occurred when the maximum object entry name length is exceeded
-    #[error("maximum object entry name length exceeded (limit: {limit}, {position})")]
+    #[error(
+        "maximum object entry name length exceeded (limit: {limit} {unit}, {position}, path: {path})"
+    )]
     MaxObjectEntryNameLengthExceeded {
         /// Position where the error occurred
         position: Position,
 
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
         /// Maximum object entry name length allowed
         limit: usize,
 
-        /// Object entry name that exceeds the limit
+        /// Unit of the length
+        unit: LengthUnit,
+
+        /// Object entry name that exceeds the limit,
+        /// only the first 64 bytes are kept by [`crate::Validator::validate_all`]
         name: String,
     },
 
     /// Error occurred when there is a duplicate object entry name
-    #[error("duplicate object entry name (key: {key}, {position})")]
+    #[error("duplicate object entry name (key: {key}, {position}, path: {path})")]
     DuplicateObjectEntryName {
         /// Position where the error occurred
         position: Position,
 
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
         /// Duplicate object entry name
         key: String,
+
+        /// Spelling of the earlier object entry name,
+        /// which differs from `key` if the names are normalized.
+        ///
+        /// The spellings are kept once the names are normalized, so it is always known then,
+        /// otherwise it is decoded from the source and spelled the same as `key`,
+        /// or `None` if the reader could not look back at the source,
+        /// such as [`crate::read::IoRead`], and [`crate::read::FeedRead`] once the earlier name is in a released chunk
+        earlier_key: Option<String>,
+    },
+
+    /// Error occurred when the memory retained to detect duplicate object entry names exceeds the limit
+    #[error(
+        "maximum duplicate detection bytes exceeded (limit: {limit}, {position}, path: {path})"
+    )]
+    MaxDuplicateDetectionBytesExceeded {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Maximum bytes retained to detect duplicate object entry names
+        limit: usize,
+    },
+
+    /// Error occurred when an object entry name is forbidden
+    #[error("forbidden object entry name (name: {name}, {position}, path: {path})")]
+    ForbiddenObjectEntryName {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// The forbidden object entry name
+        name: String,
+    },
+
+    /// Error occurred when an object entry name contains a character not allowed by the charset
+    #[error("forbidden character in object entry name (name: {name}, character: {character:?}, {position}, path: {path})")]
+    ForbiddenCharacterInObjectEntryName {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// The object entry name
+        name: String,
+
+        /// The first character not allowed by the charset
+        character: char,
+    },
+
+    /// Error occurred when a string contains a code point rejected by the Unicode checks
+    #[error(
+        "forbidden code point in string (code point: U+{code_point:04X}, {position}, path: {path})"
+    )]
+    ForbiddenCodePointInString {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// The first code point rejected by the Unicode checks
+        code_point: u32,
+    },
+
+    /// Error occurred when the memory allocated by the validator itself exceeds the limit
+    #[error("maximum validator memory exceeded (limit: {limit}, {position}, path: {path})")]
+    MaxValidatorMemoryExceeded {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Maximum bytes allocated by the validator
+        limit: usize,
+    },
+
+    /// Error occurred when a custom rule rejects the JSON, see [`crate::rule::Rule`]
+    #[error("custom rule violation ({source}, {position}, path: {path})")]
+    Custom {
+        /// Position where the error occurred
+        position: Position,
+
+        /// JSON Pointer (RFC 6901) of the value where the error occurred
+        path: String,
+
+        /// Error returned by the custom rule
+        source: RuleError,
+    },
+
+    /// Error occurred when the config sets an option of a feature which is not enabled
+    #[error("{option} requires the `{feature}` feature, which is not enabled")]
+    FeatureNotEnabled {
+        /// Name of the option in [`crate::ValidatorConfig`]
+        option: &'static str,
+
+        /// Name of the feature
+        feature: &'static str,
+    },
+
+    /// Error occurred when restoring a checkpoint whose stacks are not consistent,
+    /// such as a corrupted or forged one, see [`crate::Validator::from_checkpoint`]
+    #[error("invalid checkpoint ({reason})")]
+    InvalidCheckpoint {
+        /// Description of the inconsistency
+        reason: &'static str,
     },
 
     /// Error occurred when running into unexpected state, please report this issue to the maintainer
@@ -273,76 +926,282 @@ pub enum ValidatorError {
     },
 }
 
+impl ValidatorError {
+    /// Returns the JSON Pointer (RFC 6901) of the value where the error occurred,
+    /// or `None` if the error is not a constraint violation, such as invalid JSON.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            ValidatorError::LexerError(_)
+            | ValidatorError::InvalidJSON(_)
+            | ValidatorError::TrailingData(_)
+            | ValidatorError::FeatureNotEnabled { .. }
+            | ValidatorError::InvalidCheckpoint { .. }
+            | ValidatorError::Bug { .. } => None,
+            ValidatorError::MaxDepthExceeded { path, .. }
+            | ValidatorError::MaxPayloadSizeExceeded { path, .. }
+            | ValidatorError::MaxStringLengthExceeded { path, .. }
+            | ValidatorError::MaxNumberLengthExceeded { path, .. }
+            | ValidatorError::MaxNumberIntegerDigitsExceeded { path, .. }
+            | ValidatorError::MaxNumberFractionDigitsExceeded { path, .. }
+            | ValidatorError::MaxNumberExponentDigitsExceeded { path, .. }
+            | ValidatorError::MaxExponentMagnitudeExceeded { path, .. }
+            | ValidatorError::NumberPolicyViolation { path, .. }
+            | ValidatorError::MaxArrayEntriesExceeded { path, .. }
+            | ValidatorError::MaxObjectEntriesExceeded { path, .. }
+            | ValidatorError::MaxObjectEntryNameLengthExceeded { path, .. }
+            | ValidatorError::DuplicateObjectEntryName { path, .. }
+            | ValidatorError::MaxDuplicateDetectionBytesExceeded { path, .. }
+            | ValidatorError::MaxValidatorMemoryExceeded { path, .. }
+            | ValidatorError::ForbiddenObjectEntryName { path, .. }
+            | ValidatorError::ForbiddenCharacterInObjectEntryName { path, .. }
+            | ValidatorError::ForbiddenCodePointInString { path, .. }
+            | ValidatorError::MaxStringRawLengthExceeded { path, .. }
+            | ValidatorError::MaxStringEscapesExceeded { path, .. }
+            | ValidatorError::Custom { path, .. } => Some(path),
+        }
+    }
+}
+
 /// Interal JSON validator
 pub struct Validator<R: Read> {
     lexer: Lexer<R>,
 
+    /// Buffer of the decoded string or the number literal of the current token
+    str_buf: Vec<u8>,
+
     /// Stack of states, keep track of the current state of the validator
     states: Vec<State>,
 
     /// Stack of entries, keep track of the number of entries in the current array or object
     entires: Vec<usize>,
 
-    /// Stack of keys, keep track of the keys in the current object
-    keys: Vec<HashSet<String>>,
+    /// Stack of keys, keep track of the fingerprints of the keys in the current object
+    keys: Vec<KeySet>,
+
+    /// Computes the fingerprints of the keys
+    fingerprinter: Fingerprinter,
+
+    /// Bytes retained by the stack of keys
+    key_bytes: usize,
+
+    /// Bytes allocated by the hash tables in the stack of keys
+    key_table_bytes: usize,
+
+    /// Bytes allocated by the entry names in the stack of names
+    name_bytes: usize,
+
+    /// Stack of entry names, keep track of the current entry name in each level,
+    /// `None` for arrays and objects that have not read any entry name yet
+    names: Vec<Option<String>>,
 
     /// Current depth of the JSON
     cur_depth: usize,
 
-    max_depth: usize,
-    max_string_length: usize,
-    max_array_entries: usize,
-    max_object_entries: usize,
-    max_object_entry_name_length: usize,
-    allow_duplicate_object_entry_name: bool,
+    /// JSON Pointer of the last error, only the changed levels are built again
+    pointer_cache: PointerCache,
+
+    /// Whether to collect constraint violations instead of returning the first one
+    collect_errors: bool,
+
+    /// Whether the tokens are read as events, which need the literal of each number
+    events: bool,
+
+    /// Collected constraint violations
+    errors: Vec<ValidatorError>,
+
+    /// Custom rules, see [`Rule`]
+    rules: Vec<Box<dyn Rule>>,
+
+    /// Constraints of the validator, shared with other validators until it is modified
+    config: Arc<ValidatorConfig>,
 }
 
 impl<R: Read> Validator<R> {
-    pub fn new(
-        read: R,
-        max_depth: usize,
-        max_string_length: usize,
-        max_array_entries: usize,
-        max_object_entries: usize,
-        max_object_entry_name_length: usize,
-        allow_duplicate_object_entry_name: bool,
-    ) -> Self {
+    pub fn new(read: R, config: Arc<ValidatorConfig>) -> Self {
         let mut states = Vec::with_capacity(32);
         states.push(State::RequireElement);
 
+        let mut lexer = Lexer::new(read);
+        lexer.set_max_payload_bytes(config.max_payload_bytes);
+        lexer.set_surrogate_policy(config.surrogate_policy);
+        lexer.set_max_raw_bytes(config.max_string_raw_length);
+        lexer.set_max_escapes(config.max_string_escapes);
+        lexer.set_number_limits(&config);
+
         Validator {
-            lexer: Lexer::new(read),
+            lexer,
+            str_buf: Vec::with_capacity(64),
             states,
             entires: Vec::with_capacity(32),
             keys: Vec::with_capacity(32),
+            fingerprinter: Fingerprinter::new(),
+            key_bytes: 0,
+            key_table_bytes: 0,
+            name_bytes: 0,
+            names: Vec::with_capacity(32),
             cur_depth: 0,
+            pointer_cache: PointerCache::default(),
+            collect_errors: false,
+            events: false,
+            errors: Vec::new(),
+            rules: Vec::new(),
+            config,
+        }
+    }
+
+    pub fn from_checkpoint(mut read: R, checkpoint: Checkpoint) -> Result<Self, ValidatorError> {
+        checkpoint
+            .check()
+            .map_err(|reason| ValidatorError::InvalidCheckpoint { reason })?;
 
-            max_depth,
-            max_string_length,
-            max_array_entries,
-            max_object_entries,
-            max_object_entry_name_length,
-            allow_duplicate_object_entry_name,
+        read.resume_at(checkpoint.position);
+        let mut validator = Validator::new(read, Arc::new(checkpoint.config));
+
+        for entries in checkpoint.keys {
+            let mut keys = KeySet::with_capacity(entries.len());
+            for (fingerprint, span, spelling) in entries {
+                keys.insert(fingerprint, span, spelling.as_deref());
+            }
+            validator.key_bytes += keys.retained_bytes();
+            validator.key_table_bytes += table_bytes(&keys);
+            validator.keys.push(keys);
         }
+
+        validator.name_bytes = checkpoint
+            .names
+            .iter()
+            .flatten()
+            .map(|name| name.capacity())
+            .sum();
+        validator.names = checkpoint.names;
+        validator.states = checkpoint.states;
+        validator.entires = checkpoint.entries;
+        validator.cur_depth = checkpoint.depth;
+        validator.fingerprinter = checkpoint.fingerprinter;
+        Ok(validator)
     }
 
-    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
-        self.max_depth = max_depth;
-        self
+    /// Snapshot of the progress, which is restored by [`Validator::from_checkpoint`].
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            // The token peeked at the end of the last steps is read again once restored.
+            position: self.lexer.next_token_position(),
+            states: self.states.clone(),
+            entries: self.entires.clone(),
+            keys: self
+                .keys
+                .iter()
+                .map(|keys| {
+                    keys.iter()
+                        .map(|(fingerprint, span, spelling)| {
+                            (fingerprint, span, spelling.map(str::to_string))
+                        })
+                        .collect()
+                })
+                .collect(),
+            names: self.names.clone(),
+            depth: self.cur_depth,
+            fingerprinter: self.fingerprinter,
+            config: self.config.as_ref().clone(),
+        }
     }
 
-    pub fn with_max_string_length(mut self, max_string_length: usize) -> Self {
-        self.max_string_length = max_string_length;
+    pub fn with_config(mut self, config: Arc<ValidatorConfig>) -> Self {
+        self.lexer.set_max_payload_bytes(config.max_payload_bytes);
+        self.lexer.set_surrogate_policy(config.surrogate_policy);
+        self.lexer.set_max_raw_bytes(config.max_string_raw_length);
+        self.lexer.set_max_escapes(config.max_string_escapes);
+        self.lexer.set_number_limits(&config);
+        self.config = config;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_depth = max_depth;
+        self
+    }
+
+    pub fn with_max_payload_bytes(mut self, max_payload_bytes: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_payload_bytes = max_payload_bytes;
+        self.lexer.set_max_payload_bytes(max_payload_bytes);
+        self
+    }
+
+    pub fn with_max_string_length(mut self, max_string_length: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_string_length = max_string_length;
+        self
+    }
+
+    pub fn with_unicode_checks(mut self, unicode_checks: UnicodeChecks) -> Self {
+        Arc::make_mut(&mut self.config).unicode_checks = unicode_checks;
+        self
+    }
+
+    pub fn with_max_string_raw_length(mut self, max_string_raw_length: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_string_raw_length = max_string_raw_length;
+        self.lexer.set_max_raw_bytes(max_string_raw_length);
+        self
+    }
+
+    pub fn with_max_string_escapes(mut self, max_string_escapes: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_string_escapes = max_string_escapes;
+        self.lexer.set_max_escapes(max_string_escapes);
+        self
+    }
+
+    pub fn with_surrogate_policy(mut self, surrogate_policy: SurrogatePolicy) -> Self {
+        Arc::make_mut(&mut self.config).surrogate_policy = surrogate_policy;
+        self.lexer.set_surrogate_policy(surrogate_policy);
+        self
+    }
+
+    pub fn with_length_unit(mut self, length_unit: LengthUnit) -> Self {
+        Arc::make_mut(&mut self.config).length_unit = length_unit;
+        self
+    }
+
+    pub fn with_max_number_length(mut self, max_number_length: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_number_length = max_number_length;
+        self.lexer.set_number_limits(&self.config);
+        self
+    }
+
+    pub fn with_max_number_integer_digits(mut self, max_number_integer_digits: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_number_integer_digits = max_number_integer_digits;
+        self.lexer.set_number_limits(&self.config);
+        self
+    }
+
+    pub fn with_max_number_fraction_digits(mut self, max_number_fraction_digits: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_number_fraction_digits = max_number_fraction_digits;
+        self.lexer.set_number_limits(&self.config);
+        self
+    }
+
+    pub fn with_max_number_exponent_digits(mut self, max_number_exponent_digits: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_number_exponent_digits = max_number_exponent_digits;
+        self.lexer.set_number_limits(&self.config);
+        self
+    }
+
+    pub fn with_max_exponent_magnitude(mut self, max_exponent_magnitude: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_exponent_magnitude = max_exponent_magnitude;
+        self.lexer.set_number_limits(&self.config);
+        self
+    }
+
+    pub fn with_number_policy(mut self, number_policy: NumberPolicy) -> Self {
+        Arc::make_mut(&mut self.config).number_policy = number_policy;
         self
     }
 
     pub fn with_max_array_entries(mut self, max_array_entries: usize) -> Self {
-        self.max_array_entries = max_array_entries;
+        Arc::make_mut(&mut self.config).max_array_entries = max_array_entries;
         self
     }
 
     pub fn with_max_object_entries(mut self, max_object_entries: usize) -> Self {
-        self.max_object_entries = max_object_entries;
+        Arc::make_mut(&mut self.config).max_object_entries = max_object_entries;
         self
     }
 
@@ -350,95 +1209,265 @@ impl<R: Read> Validator<R> {
         mut self,
         max_object_entry_name_length: usize,
     ) -> Self {
-        self.max_object_entry_name_length = max_object_entry_name_length;
+        Arc::make_mut(&mut self.config).max_object_entry_name_length = max_object_entry_name_length;
+        self
+    }
+
+    pub fn with_max_duplicate_detection_bytes(
+        mut self,
+        max_duplicate_detection_bytes: usize,
+    ) -> Self {
+        Arc::make_mut(&mut self.config).max_duplicate_detection_bytes =
+            max_duplicate_detection_bytes;
         self
     }
 
     pub fn allow_duplicate_object_entry_name(mut self) -> Self {
-        self.allow_duplicate_object_entry_name = true;
+        Arc::make_mut(&mut self.config).allow_duplicate_object_entry_name = true;
         self
     }
 
     pub fn disallow_duplicate_object_entry_name(mut self) -> Self {
-        self.allow_duplicate_object_entry_name = false;
+        Arc::make_mut(&mut self.config).allow_duplicate_object_entry_name = false;
+        self
+    }
+
+    pub fn with_rule(mut self, rule: Box<dyn Rule>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn with_prototype_pollution_policy(
+        mut self,
+        prototype_pollution_policy: PrototypePollutionPolicy,
+    ) -> Self {
+        Arc::make_mut(&mut self.config).prototype_pollution_policy = prototype_pollution_policy;
         self
     }
 
+    pub fn with_key_policy(mut self, key_policy: KeyPolicy) -> Self {
+        Arc::make_mut(&mut self.config)
+            .key_policies
+            .push(key_policy);
+        self
+    }
+
+    pub fn with_key_charset(mut self, key_charset: KeyCharset) -> Self {
+        Arc::make_mut(&mut self.config).key_charset = key_charset;
+        self
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    pub fn with_key_normalization(mut self, key_normalization: KeyNormalization) -> Self {
+        Arc::make_mut(&mut self.config).key_normalization = key_normalization;
+        self
+    }
+
+    pub fn with_max_validator_memory(mut self, max_validator_memory: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_validator_memory = max_validator_memory;
+        self
+    }
+
+    pub fn with_max_errors(mut self, max_errors: usize) -> Self {
+        Arc::make_mut(&mut self.config).max_errors = max_errors;
+        self
+    }
+
+    pub fn validate_all(mut self) -> Result<(), Vec<ValidatorError>> {
+        self.collect_errors = true;
+
+        loop {
+            match self.validate_with_steps(usize::MAX) {
+                Ok(true) if self.errors.is_empty() => return Ok(()),
+                Ok(true) => return Err(self.errors),
+                Ok(false) => (),
+                Err(e) => {
+                    self.errors.push(e);
+                    return Err(self.errors);
+                }
+            }
+        }
+    }
+
     pub fn validate(mut self) -> Result<(), ValidatorError> {
         loop {
-            match self.validate_with_steps(usize::MAX)? {
-                true => return Ok(()),
-                false => (),
+            if self.validate_with_steps(usize::MAX)? {
+                return Ok(());
             }
         }
     }
 
     pub fn validate_with_steps(&mut self, steps: usize) -> Result<bool, ValidatorError> {
-        match self.inner_validate(steps) {
-            Ok(finished) => Ok(finished),
-            Err(e) => Err(self.correct_position(e)),
+        let result = self.inner_validate(steps);
+        self.conclude(result)
+    }
+
+    pub fn next_event(&mut self) -> Result<Option<EventKind>, ValidatorError> {
+        let result = self.inner_next_event();
+        self.conclude(result)
+    }
+
+    /// Validate the bytes available so far,
+    /// and rewind to the start of the partial token once the reader needs more input.
+    pub fn resume(&mut self) -> Result<Progress, ValidatorError> {
+        let result = self.inner_resume();
+        self.conclude(result)
+    }
+
+    /// Decoded string of the last key or string event, or the literal of the last number event.
+    pub fn token_str(&self) -> &str {
+        // The lexer has checked the string is valid UTF-8,
+        // and the number literal is always ASCII.
+        unsafe { std::str::from_utf8_unchecked(self.str_buf.as_slice()) }
+    }
+
+    /// Position right before the first byte of the last token.
+    pub fn token_start(&self) -> Position {
+        self.lexer.token_start()
+    }
+
+    /// JSON Pointer (RFC 6901) of the current value.
+    pub fn path(&self) -> String {
+        self.pointer()
+    }
+
+    /// Check the payload size and correct the position of the error.
+    fn conclude<T>(&mut self, result: Result<T, ValidatorError>) -> Result<T, ValidatorError> {
+        // The reader might stop consuming the source once the payload size limit is exceeded,
+        // which looks like an unexpected end of input or even a complete JSON for the lexer,
+        // so the payload size takes precedence over any other results.
+        if self.lexer.position().offset > self.config.max_payload_bytes {
+            return Err(ValidatorError::MaxPayloadSizeExceeded {
+                position: self.lexer.position(),
+                path: self.cached_pointer(),
+                limit: self.config.max_payload_bytes,
+            });
         }
+
+        result.map_err(|e| self.correct_position(e))
     }
 
-    /// Correct the position of the error
-    fn correct_position(&self, err: ValidatorError) -> ValidatorError {
-        match err {
-            ValidatorError::InvalidJSON(_) => ValidatorError::InvalidJSON(self.lexer.position()),
-            ValidatorError::TrailingData(_) => ValidatorError::TrailingData(self.lexer.position()),
-            ValidatorError::MaxDepthExceeded { position: _, limit } => {
-                ValidatorError::MaxDepthExceeded {
-                    position: self.lexer.position(),
-                    limit,
-                }
+    /// Correct the position and the path of the error
+    fn correct_position(&mut self, err: ValidatorError) -> ValidatorError {
+        let position = self.lexer.position();
+        locate(err, position, || self.cached_pointer())
+    }
+
+    /// Build the JSON Pointer (RFC 6901) of the current value.
+    fn pointer(&self) -> String {
+        pointer(&self.states, &self.entires, &self.names)
+    }
+
+    /// Same as [`Validator::pointer`], but only builds the levels changed since the last call,
+    /// so the errors collected in a deeply nested payload do not build the whole path each time.
+    fn cached_pointer(&mut self) -> String {
+        self.pointer_cache
+            .pointer(&self.states, &self.entires, &self.names)
+    }
+
+    /// Copy of the string in an error,
+    /// only a prefix is kept when collecting errors so they never copy the whole payload.
+    fn excerpt(&self, str: &str) -> String {
+        match self.collect_errors {
+            true => prefix(str, COLLECTED_EXCERPT_BYTES).to_string(),
+            false => str.to_string(),
+        }
+    }
+
+    fn inner_validate(&mut self, steps: usize) -> Result<bool, ValidatorError> {
+        check_config(&self.config)?;
+        let mut remaining_steps = steps;
+
+        while let Some(state) = self.states.pop() {
+            self.step(state)?;
+
+            remaining_steps -= 1;
+            if remaining_steps == 0 {
+                break;
             }
-            ValidatorError::MaxStringLengthExceeded {
-                position: _,
-                limit,
-                str,
-            } => ValidatorError::MaxStringLengthExceeded {
-                position: self.lexer.position(),
-                limit,
-                str,
-            },
-            ValidatorError::MaxArrayEntriesExceeded { position: _, limit } => {
-                ValidatorError::MaxArrayEntriesExceeded {
-                    position: self.lexer.position(),
-                    limit,
+        }
+
+        self.check_end(remaining_steps)
+    }
+
+    fn inner_resume(&mut self) -> Result<Progress, ValidatorError> {
+        check_config(&self.config)?;
+        loop {
+            // Skip the whitespace before marking,
+            // so that the whitespace is never retained for the partial token.
+            let skipped = self.lexer.skip_whitespace();
+            self.lexer.mark();
+            if let Err(e) = skipped {
+                if !is_need_more_input(&e) {
+                    return Err(e.into());
                 }
+                return Ok(self.progress());
             }
-            ValidatorError::MaxObjectEntriesExceeded { position: _, limit } => {
-                ValidatorError::MaxObjectEntriesExceeded {
-                    position: self.lexer.position(),
-                    limit,
+
+            let state = match self.states.pop() {
+                Some(state) => state,
+                None => break,
+            };
+
+            match self.step(state.clone()) {
+                Ok(_) => (),
+                Err(ValidatorError::LexerError(e)) if is_need_more_input(&e) => {
+                    self.states.push(state);
+                    self.lexer.rewind();
+                    return Ok(Progress::NeedMoreInput);
                 }
+                Err(e) => return Err(e),
             }
-            ValidatorError::MaxObjectEntryNameLengthExceeded {
-                position: _,
-                limit,
-                name,
-            } => ValidatorError::MaxObjectEntryNameLengthExceeded {
-                position: self.lexer.position(),
-                limit,
-                name,
-            },
-            ValidatorError::DuplicateObjectEntryName { position: _, key } => {
-                ValidatorError::DuplicateObjectEntryName {
-                    position: self.lexer.position(),
-                    key,
-                }
+        }
+
+        match self.check_end(0) {
+            Ok(_) => Ok(Progress::Complete),
+            // Any partial token after a complete JSON value is trailing data.
+            Err(ValidatorError::LexerError(e)) if is_need_more_input(&e) => {
+                Err(ValidatorError::TrailingData(Position::default()))
             }
-            ValidatorError::LexerError(e) => ValidatorError::LexerError(e),
-            ValidatorError::Bug { msg, position: _ } => ValidatorError::Bug {
-                msg,
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Validate the rest of the input once the reader is finished.
+    fn resume_finished(&mut self) -> Result<(), ValidatorError> {
+        match self.resume()? {
+            Progress::Complete => Ok(()),
+            Progress::NeedMoreInput => Err(ValidatorError::Bug {
+                msg:
+                    "Validator.resume_finished: the reader should not need more input once finished"
+                        .to_string(),
                 position: self.lexer.position(),
-            },
+            }),
         }
     }
 
-    fn inner_validate(&mut self, steps: usize) -> Result<bool, ValidatorError> {
-        let mut remaining_steps = steps;
-        let mut str_buf = Vec::with_capacity(64);
+    fn progress(&self) -> Progress {
+        if self.states.is_empty() {
+            Progress::Complete
+        } else {
+            Progress::NeedMoreInput
+        }
+    }
+
+    fn inner_next_event(&mut self) -> Result<Option<EventKind>, ValidatorError> {
+        check_config(&self.config)?;
+        self.events = true;
+        while let Some(state) = self.states.pop() {
+            if let Some(kind) = self.step(state)? {
+                return Ok(Some(kind));
+            }
+        }
+
+        self.check_end(0)?;
+        Ok(None)
+    }
 
+    /// Process the next token with the given state,
+    /// returns the kind of event produced by the token,
+    /// or `None` if the token is a comma or a colon.
+    fn step(&mut self, state: State) -> Result<Option<EventKind>, ValidatorError> {
         // Dummy position for constructing `ValidatorError`
         // so that we can keep the error reason once the error occurs,
         // and the real position will be updated by the caller of this method.
@@ -449,84 +1478,205 @@ impl<R: Read> Validator<R> {
         // This is a workaround to make the borrow checker happy.
         let dummy_position = Position::default();
 
-        while let Some(state) = self.states.pop() {
-            let token = self.lexer.next(&mut str_buf)?;
+        self.limit_token_buffer();
+        let token = self
+            .lexer
+            .next(&mut self.str_buf)
+            .map_err(|err| self.lexer_error(err, &state, self.states.len()))?;
+        try_check_payload_size!(self, dummy_position);
+
+        if token.is_none() {
+            return Err(ValidatorError::InvalidJSON(dummy_position));
+        }
+
+        // unwrap is safe here since we have checked the token is not None.
+        let kind = match token.unwrap() {
+            Token::LBrace => match state {
+                State::RequireElement | State::OptionalElement => {
+                    try_add_entry!(self, dummy_position);
+                    try_apply_rules!(self, dummy_position, on_start_object);
+                    self.states.push(State::ProcessingObject);
+                    self.states.push(State::OptionalObjectKey);
+                    try_active_object!(self, dummy_position);
+                    Some(EventKind::StartObject)
+                }
+                _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
+            },
+            Token::RBrace => {
+                match state {
+                    State::OptionalComma | State::OptionalObjectKey => {
+                        let state = self
+                            .states
+                            .pop()
+                            .ok_or(ValidatorError::InvalidJSON(dummy_position))?;
+                        if state != State::ProcessingObject {
+                            return Err(ValidatorError::InvalidJSON(dummy_position));
+                        }
 
-            if token.is_none() {
-                return Err(ValidatorError::InvalidJSON(dummy_position));
+                        let entires = try_finalize_object!(self, dummy_position);
+                        if state == State::OptionalObjectKey && entires != 0 {
+                            return Err(ValidatorError::InvalidJSON(dummy_position));
+                        }
+                    }
+                    State::ProcessingObject => {
+                        if try_finalize_object!(self, dummy_position) == 0 {
+                            return Err(ValidatorError::InvalidJSON(dummy_position));
+                        }
+                    }
+                    _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
+                }
+
+                try_apply_rules!(self, dummy_position, on_end_object);
+
+                if matches!(
+                    self.states.last(),
+                    Some(State::ProcessingArray | State::ProcessingObject)
+                ) {
+                    self.states.push(State::OptionalComma);
+                }
+
+                Some(EventKind::EndObject)
             }
+            Token::LBracket => match state {
+                State::RequireElement | State::OptionalElement => {
+                    try_add_entry!(self, dummy_position);
+                    try_apply_rules!(self, dummy_position, on_start_array);
+                    self.states.push(State::ProcessingArray);
+                    self.states.push(State::OptionalElement);
+                    try_active_array!(self, dummy_position);
+                    Some(EventKind::StartArray)
+                }
+                _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
+            },
+            Token::RBracket => {
+                match state {
+                    State::OptionalComma | State::OptionalElement => {
+                        let state = self
+                            .states
+                            .pop()
+                            .ok_or(ValidatorError::InvalidJSON(dummy_position))?;
+                        if state != State::ProcessingArray {
+                            return Err(ValidatorError::InvalidJSON(dummy_position));
+                        }
 
-            // unwrap is safe here since we have checked the token is not None.
-            match token.unwrap() {
-                Token::LBrace => match state {
-                    State::RequireElement | State::OptionalElement => {
-                        self.states.push(State::ProcessingObject);
-                        self.states.push(State::OptionalObjectKey);
-                        try_active_object!(self, dummy_position);
+                        let entries = try_finalize_array!(self, dummy_position);
+                        if state == State::OptionalElement && entries != 0 {
+                            return Err(ValidatorError::InvalidJSON(dummy_position));
+                        }
+                    }
+                    State::ProcessingArray => {
+                        if try_finalize_array!(self, dummy_position) == 0 {
+                            return Err(ValidatorError::InvalidJSON(dummy_position));
+                        }
+                    }
+                    _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
+                }
+
+                try_apply_rules!(self, dummy_position, on_end_array);
+
+                if matches!(
+                    self.states.last(),
+                    Some(State::ProcessingArray | State::ProcessingObject)
+                ) {
+                    self.states.push(State::OptionalComma);
+                }
+
+                Some(EventKind::EndArray)
+            }
+            Token::Colon => match state {
+                State::RequireColon => {
+                    self.states.push(State::RequireElement);
+                    None
+                }
+                _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
+            },
+            Token::Comma => match state {
+                State::OptionalComma => match self.states.last() {
+                    Some(State::ProcessingObject) => {
+                        self.states.push(State::RequireObjectKey);
+                        None
+                    }
+                    Some(State::ProcessingArray) => {
+                        self.states.push(State::RequireElement);
+                        None
                     }
                     _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
                 },
-                Token::RBrace => {
-                    match state {
-                        State::OptionalComma | State::OptionalObjectKey => {
-                            let state = self
-                                .states
-                                .pop()
-                                .ok_or(ValidatorError::InvalidJSON(dummy_position))?;
-                            if state != State::ProcessingObject {
-                                return Err(ValidatorError::InvalidJSON(dummy_position));
+                _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
+            },
+            Token::String => match state {
+                State::OptionalObjectKey | State::RequireObjectKey => {
+                    let str = unsafe { std::str::from_utf8_unchecked(self.str_buf.as_slice()) };
+                    try_add_object_key!(self, str, dummy_position);
+                    try_check_raw_string!(self, dummy_position);
+                    try_apply_rules!(self, dummy_position, on_key, str);
+                    self.states.push(State::RequireColon);
+                    Some(EventKind::Key)
+                }
+                State::OptionalElement | State::RequireElement => {
+                    try_add_entry!(self, dummy_position);
+                    try_check_raw_string!(self, dummy_position);
+
+                    let str = unsafe { std::str::from_utf8_unchecked(self.str_buf.as_slice()) };
+                    if self
+                        .config
+                        .length_unit
+                        .exceeds(str, self.config.max_string_length)
+                    {
+                        report!(
+                            self,
+                            ValidatorError::MaxStringLengthExceeded {
+                                position: dummy_position,
+                                path: String::new(),
+                                limit: self.config.max_string_length,
+                                unit: self.config.length_unit,
+                                str: self.excerpt(str),
                             }
+                        );
+                    }
 
-                            let entires = try_finalize_object!(self, dummy_position);
-                            if state == State::OptionalObjectKey && entires != 0 {
-                                return Err(ValidatorError::InvalidJSON(dummy_position));
-                            }
-                        }
-                        State::ProcessingObject => {
-                            if try_finalize_object!(self, dummy_position) == 0 {
-                                return Err(ValidatorError::InvalidJSON(dummy_position));
+                    if let Some(character) = self.config.unicode_checks.find_rejected(str) {
+                        report!(
+                            self,
+                            ValidatorError::ForbiddenCodePointInString {
+                                position: dummy_position,
+                                path: String::new(),
+                                code_point: character as u32,
                             }
-                        }
-                        _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
+                        );
                     }
 
+                    try_apply_rules!(self, dummy_position, on_string, str);
+
                     if matches!(
                         self.states.last(),
                         Some(State::ProcessingArray | State::ProcessingObject)
                     ) {
                         self.states.push(State::OptionalComma);
                     }
-                }
-                Token::LBracket => match state {
-                    State::RequireElement | State::OptionalElement => {
-                        self.states.push(State::ProcessingArray);
-                        self.states.push(State::OptionalElement);
-                        try_active_array!(self, dummy_position);
-                    }
-                    _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
-                },
-                Token::RBracket => {
-                    match state {
-                        State::OptionalComma | State::OptionalElement => {
-                            let state = self
-                                .states
-                                .pop()
-                                .ok_or(ValidatorError::InvalidJSON(dummy_position))?;
-                            if state != State::ProcessingArray {
-                                return Err(ValidatorError::InvalidJSON(dummy_position));
-                            }
 
-                            let entries = try_finalize_array!(self, dummy_position);
-                            if state == State::OptionalElement && entries != 0 {
-                                return Err(ValidatorError::InvalidJSON(dummy_position));
-                            }
+                    Some(EventKind::String)
+                }
+                _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
+            },
+            Token::Number | Token::True | Token::False | Token::Null => match state {
+                State::OptionalElement | State::RequireElement => {
+                    try_add_entry!(self, dummy_position);
+
+                    match token {
+                        Some(Token::Number) => {
+                            let number = self.str_buf.as_slice();
+                            try_check_number!(self, number, dummy_position);
+
+                            // The number literal is always ASCII.
+                            let literal = unsafe { std::str::from_utf8_unchecked(number) };
+                            try_apply_rules!(self, dummy_position, on_number, literal);
                         }
-                        State::ProcessingArray => {
-                            if try_finalize_array!(self, dummy_position) == 0 {
-                                return Err(ValidatorError::InvalidJSON(dummy_position));
-                            }
+                        Some(Token::True) => try_apply_rules!(self, dummy_position, on_bool, true),
+                        Some(Token::False) => {
+                            try_apply_rules!(self, dummy_position, on_bool, false)
                         }
-                        _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
+                        _ => try_apply_rules!(self, dummy_position, on_null),
                     }
 
                     if matches!(
@@ -535,74 +1685,132 @@ impl<R: Read> Validator<R> {
                     ) {
                         self.states.push(State::OptionalComma);
                     }
-                }
-                Token::Colon => match state {
-                    State::RequireColon => self.states.push(State::RequireElement),
-                    _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
-                },
-                Token::Comma => match state {
-                    State::OptionalComma => match self.states.last() {
-                        Some(State::ProcessingObject) => self.states.push(State::RequireObjectKey),
-                        Some(State::ProcessingArray) => self.states.push(State::RequireElement),
-                        _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
-                    },
-                    _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
-                },
-                Token::String => match state {
-                    State::OptionalObjectKey | State::RequireObjectKey => {
-                        let str = unsafe { std::str::from_utf8_unchecked(str_buf.as_slice()) };
-                        try_add_object_key!(self, str, dummy_position);
-                        self.states.push(State::RequireColon);
-                    }
-                    State::OptionalElement | State::RequireElement => {
-                        let str = unsafe { std::str::from_utf8_unchecked(str_buf.as_slice()) };
-                        if str.len() > self.max_string_length {
-                            return Err(ValidatorError::MaxStringLengthExceeded {
-                                position: dummy_position,
-                                limit: self.max_string_length,
-                                str: str.to_string(),
-                            });
-                        }
 
-                        let last_state = self.states.last_mut();
-                        match last_state {
-                            Some(State::ProcessingObject) => {
-                                try_add_object_value!(self, dummy_position);
-                                self.states.push(State::OptionalComma);
-                            }
-                            Some(State::ProcessingArray) => {
-                                try_add_array_entry!(self, dummy_position);
-                                self.states.push(State::OptionalComma);
-                            }
-                            _ => (),
-                        }
+                    match token {
+                        Some(Token::Number) => Some(EventKind::Number),
+                        Some(Token::True) => Some(EventKind::True),
+                        Some(Token::False) => Some(EventKind::False),
+                        _ => Some(EventKind::Null),
                     }
-                    _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
-                },
-                Token::Number | Token::True | Token::False | Token::Null => match state {
-                    State::OptionalElement | State::RequireElement => {
-                        let last_state = self.states.last_mut();
-                        match last_state {
-                            Some(State::ProcessingObject) => {
-                                try_add_object_value!(self, dummy_position);
-                                self.states.push(State::OptionalComma);
-                            }
-                            Some(State::ProcessingArray) => {
-                                try_add_array_entry!(self, dummy_position);
-                                self.states.push(State::OptionalComma);
-                            }
-                            _ => (),
-                        }
-                    }
-                    _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
-                },
-            }
+                }
+                _ => return Err(ValidatorError::InvalidJSON(dummy_position)),
+            },
+        };
 
-            remaining_steps -= 1;
-            if remaining_steps == 0 {
-                break;
+        try_check_validator_memory!(self, dummy_position);
+
+        Ok(kind)
+    }
+
+    /// Bytes allocated by the validator itself,
+    /// which are the stacks, the hash tables of the keys, the string buffers and the buffer of the reader.
+    fn memory(&self) -> usize {
+        self.states.capacity() * std::mem::size_of::<State>()
+            + self.entires.capacity() * std::mem::size_of::<usize>()
+            + self.keys.capacity() * std::mem::size_of::<KeySet>()
+            + self.key_table_bytes
+            + self.names.capacity() * std::mem::size_of::<Option<String>>()
+            + self.name_bytes
+            + self.str_buf.capacity()
+            + self.lexer.buffer_capacity()
+    }
+
+    /// Limits the buffer of the next token to the memory left by the rest of the validator,
+    /// so an oversized string or number is rejected before it is buffered entirely,
+    /// and only buffers the literal of a number once the policy, a rule or an event needs it.
+    fn limit_token_buffer(&mut self) {
+        self.lexer.set_buffer_numbers(
+            self.events
+                || !self.rules.is_empty()
+                || self.config.number_policy != NumberPolicy::ArbitraryPrecision,
+        );
+        if self.config.max_validator_memory != NO_LIMIT {
+            let others = self.memory() - self.str_buf.capacity();
+            self.lexer
+                .set_max_buffer_bytes(self.config.max_validator_memory.saturating_sub(others));
+        }
+    }
+
+    /// Reports the limits of the token checked by the lexer as the constraint violations,
+    /// where the token is read with the `state` on top of `self.states[..top]`.
+    fn lexer_error(&mut self, err: LexerError, state: &State, top: usize) -> ValidatorError {
+        let err = match err {
+            LexerError::ReadError(ReadError::BufferLimitExceeded(position)) => {
+                ValidatorError::MaxValidatorMemoryExceeded {
+                    position,
+                    path: String::new(),
+                    limit: self.config.max_validator_memory,
+                }
+            }
+            LexerError::ReadError(ReadError::RawLengthExceeded(position)) => {
+                ValidatorError::MaxStringRawLengthExceeded {
+                    position,
+                    path: String::new(),
+                    limit: self.config.max_string_raw_length,
+                }
+            }
+            LexerError::ReadError(ReadError::EscapesExceeded(position)) => {
+                ValidatorError::MaxStringEscapesExceeded {
+                    position,
+                    path: String::new(),
+                    limit: self.config.max_string_escapes,
+                }
+            }
+            LexerError::ReadError(ReadError::NumberLengthExceeded(position)) => {
+                ValidatorError::MaxNumberLengthExceeded {
+                    position,
+                    path: String::new(),
+                    limit: self.config.max_number_length,
+                }
+            }
+            LexerError::ReadError(ReadError::IntegerDigitsExceeded(position)) => {
+                ValidatorError::MaxNumberIntegerDigitsExceeded {
+                    position,
+                    path: String::new(),
+                    limit: self.config.max_number_integer_digits,
+                }
+            }
+            LexerError::ReadError(ReadError::FractionDigitsExceeded(position)) => {
+                ValidatorError::MaxNumberFractionDigitsExceeded {
+                    position,
+                    path: String::new(),
+                    limit: self.config.max_number_fraction_digits,
+                }
+            }
+            LexerError::ReadError(ReadError::ExponentDigitsExceeded(position)) => {
+                ValidatorError::MaxNumberExponentDigitsExceeded {
+                    position,
+                    path: String::new(),
+                    limit: self.config.max_number_exponent_digits,
+                }
+            }
+            LexerError::ReadError(ReadError::ExponentMagnitudeExceeded(position)) => {
+                ValidatorError::MaxExponentMagnitudeExceeded {
+                    position,
+                    path: String::new(),
+                    limit: self.config.max_exponent_magnitude,
+                }
+            }
+            err => return err.into(),
+        };
+
+        // An array entry is only counted once it is read,
+        // so count it here to point the path of the error at the entry.
+        let in_array = top > 0 && self.states[top - 1] == State::ProcessingArray;
+        if in_array && matches!(state, State::RequireElement | State::OptionalElement) {
+            self.pointer_cache
+                .invalidate(self.entires.len().saturating_sub(1));
+            if let Some(entries) = self.entires.last_mut() {
+                *entries += 1;
             }
         }
+        err
+    }
+
+    /// Check whether the validation is finished once there is no state to process
+    /// or the `remaining_steps` are exhausted.
+    fn check_end(&mut self, remaining_steps: usize) -> Result<bool, ValidatorError> {
+        let dummy_position = Position::default();
 
         // At this point, there are four possible cases:
         // * The JSON is valid and there is no more token to process.
@@ -616,7 +1824,16 @@ impl<R: Read> Validator<R> {
 
         let has_states = !self.states.is_empty();
         let no_depth = self.cur_depth == 0;
-        let has_more_token = self.lexer.peek(&mut str_buf)?.is_some();
+        self.limit_token_buffer();
+        let has_more_token = self
+            .lexer
+            .peek(&mut self.str_buf)
+            .map_err(|err| match self.states.last().cloned() {
+                Some(state) => self.lexer_error(err, &state, self.states.len() - 1),
+                None => err.into(),
+            })?
+            .is_some();
+        try_check_validator_memory!(self, dummy_position);
 
         if has_states || !no_depth {
             if has_more_token {
@@ -653,3 +1870,202 @@ impl<R: Read> Validator<R> {
         Err(ValidatorError::TrailingData(dummy_position))
     }
 }
+
+impl Validator<FeedRead> {
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Progress, ValidatorError> {
+        let reader = self.lexer.reader_mut();
+        reader.feed(chunk);
+        if !reader.is_ready() {
+            return Ok(self.progress());
+        }
+
+        self.resume()
+    }
+
+    pub fn finish(mut self) -> Result<(), ValidatorError> {
+        self.lexer.reader_mut().finish();
+        self.resume_finished()
+    }
+}
+
+impl<R: std::io::Read> Validator<IoRead<R>> {
+    pub fn validate_nonblocking(&mut self) -> Result<bool, ValidatorError> {
+        match self.lexer.reader_mut().fill_pending() {
+            Ok(true) => (),
+            Ok(false) => return Ok(false),
+            Err(e) => return self.conclude(Err(LexerError::from(e).into())),
+        }
+
+        // The validation is finished only once the end of input is reached,
+        // a complete JSON value might still be followed by trailing data.
+        match self.resume()? {
+            Progress::Complete => Ok(!self.lexer.reader().would_block()),
+            Progress::NeedMoreInput => Ok(false),
+        }
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+impl<S: AsyncSource + Unpin> Validator<AsyncIoRead<S>> {
+    pub async fn validate_async(mut self) -> Result<(), ValidatorError> {
+        loop {
+            let reader = self.lexer.reader_mut();
+            match reader.fill().await {
+                Ok(true) if reader.is_ready() => {
+                    self.resume()?;
+                }
+                Ok(true) => (),
+                Ok(false) => return self.resume_finished(),
+                Err(e) => return self.conclude(Err(LexerError::from(e).into())),
+            }
+        }
+    }
+}
+
+fn is_need_more_input(err: &LexerError) -> bool {
+    matches!(err, LexerError::ReadError(ReadError::NeedMoreInput(_)))
+}
+
+/// Set the position and the path of the error, the path is only built if the error has one.
+fn locate(
+    mut err: ValidatorError,
+    cur_position: Position,
+    pointer: impl FnOnce() -> String,
+) -> ValidatorError {
+    match &mut err {
+        ValidatorError::LexerError(_)
+        | ValidatorError::FeatureNotEnabled { .. }
+        | ValidatorError::InvalidCheckpoint { .. } => (),
+        ValidatorError::InvalidJSON(position)
+        | ValidatorError::TrailingData(position)
+        | ValidatorError::Bug { position, .. } => *position = cur_position,
+        ValidatorError::MaxDepthExceeded { position, path, .. }
+        | ValidatorError::MaxPayloadSizeExceeded { position, path, .. }
+        | ValidatorError::MaxStringLengthExceeded { position, path, .. }
+        | ValidatorError::MaxNumberLengthExceeded { position, path, .. }
+        | ValidatorError::MaxNumberIntegerDigitsExceeded { position, path, .. }
+        | ValidatorError::MaxNumberFractionDigitsExceeded { position, path, .. }
+        | ValidatorError::MaxNumberExponentDigitsExceeded { position, path, .. }
+        | ValidatorError::MaxExponentMagnitudeExceeded { position, path, .. }
+        | ValidatorError::NumberPolicyViolation { position, path, .. }
+        | ValidatorError::MaxArrayEntriesExceeded { position, path, .. }
+        | ValidatorError::MaxObjectEntriesExceeded { position, path, .. }
+        | ValidatorError::MaxObjectEntryNameLengthExceeded { position, path, .. }
+        | ValidatorError::DuplicateObjectEntryName { position, path, .. }
+        | ValidatorError::MaxDuplicateDetectionBytesExceeded { position, path, .. }
+        | ValidatorError::MaxValidatorMemoryExceeded { position, path, .. }
+        | ValidatorError::ForbiddenObjectEntryName { position, path, .. }
+        | ValidatorError::ForbiddenCharacterInObjectEntryName { position, path, .. }
+        | ValidatorError::ForbiddenCodePointInString { position, path, .. }
+        | ValidatorError::MaxStringRawLengthExceeded { position, path, .. }
+        | ValidatorError::MaxStringEscapesExceeded { position, path, .. }
+        | ValidatorError::Custom { position, path, .. } => {
+            *position = cur_position;
+            *path = pointer();
+        }
+    }
+
+    err
+}
+
+/// Build the JSON Pointer (RFC 6901) of the current value
+/// from the stacks of states, entries and entry names.
+pub(crate) fn pointer(states: &[State], entries: &[usize], names: &[Option<String>]) -> String {
+    let mut pointer = String::new();
+    let containers = states
+        .iter()
+        .filter(|state| matches!(state, State::ProcessingArray | State::ProcessingObject));
+
+    for (level, container) in containers.enumerate() {
+        if !push_segment(
+            &mut pointer,
+            container,
+            entries.get(level),
+            names.get(level),
+        ) {
+            break;
+        }
+    }
+
+    pointer
+}
+
+/// Append the reference token of a level to the JSON Pointer,
+/// returns `false` if the container has not read any entry yet.
+fn push_segment(
+    pointer: &mut String,
+    container: &State,
+    entries: Option<&usize>,
+    name: Option<&Option<String>>,
+) -> bool {
+    match container {
+        State::ProcessingArray => match entries {
+            Some(&count) if count > 0 => {
+                pointer.push('/');
+                pointer.push_str(&(count - 1).to_string());
+                true
+            }
+            _ => false,
+        },
+        _ => match name {
+            Some(Some(name)) => {
+                pointer.push('/');
+                pointer.push_str(&name.replace('~', "~0").replace('/', "~1"));
+                true
+            }
+            _ => false,
+        },
+    }
+}
+
+/// JSON Pointer built by [`Validator::cached_pointer`].
+#[derive(Debug, Default)]
+struct PointerCache {
+    pointer: String,
+
+    /// End of the reference token of each level in `pointer`
+    ends: Vec<usize>,
+
+    /// Lowest level changed since the pointer was built
+    dirty: usize,
+}
+
+impl PointerCache {
+    /// Mark the reference token of the level and the deeper ones as changed.
+    fn invalidate(&mut self, level: usize) {
+        self.dirty = self.dirty.min(level);
+    }
+
+    /// Same as [`pointer`], but only builds the levels changed since the last call.
+    fn pointer(&mut self, states: &[State], entries: &[usize], names: &[Option<String>]) -> String {
+        self.ends.truncate(self.dirty);
+
+        let containers = states
+            .iter()
+            .filter(|state| matches!(state, State::ProcessingArray | State::ProcessingObject));
+
+        let mut levels = 0;
+        for (level, container) in containers.enumerate() {
+            if level == self.ends.len() {
+                let start = self.ends.last().copied().unwrap_or(0);
+                self.pointer.truncate(start);
+                if !push_segment(
+                    &mut self.pointer,
+                    container,
+                    entries.get(level),
+                    names.get(level),
+                ) {
+                    break;
+                }
+                self.ends.push(self.pointer.len());
+            }
+            levels = level + 1;
+        }
+        self.dirty = self.ends.len();
+
+        match levels {
+            0 => String::new(),
+            levels => self.pointer[..self.ends[levels - 1]].to_string(),
+        }
+    }
+}
@@ -37,7 +37,9 @@ fn limit_string() {
     match result.unwrap_err() {
         jtp::Error::MaxStringLengthExceeded {
             position,
+            path,
             limit,
+            unit,
             str,
         } => {
             assert_eq!(
@@ -49,6 +51,8 @@ fn limit_string() {
                 }
             );
             assert_eq!(limit, 5);
+            assert_eq!(unit, jtp::LengthUnit::Bytes);
+            assert_eq!(path, "");
             assert_eq!(str, "123456");
         }
         _ => panic!("unexpected error"),
@@ -66,7 +70,9 @@ fn limit_string() {
     match result.unwrap_err() {
         jtp::Error::MaxStringLengthExceeded {
             position,
+            path,
             limit,
+            unit,
             str,
         } => {
             assert_eq!(
@@ -78,6 +84,8 @@ fn limit_string() {
                 }
             );
             assert_eq!(limit, 5);
+            assert_eq!(unit, jtp::LengthUnit::Bytes);
+            assert_eq!(path, "");
             assert_eq!(str, "123456");
         }
         _ => panic!("unexpected error"),
@@ -96,7 +104,11 @@ fn limit_array() {
     let result = jtp::from_slice(&v).with_max_array_entries(4).validate();
     assert!(result.is_err());
     match result.unwrap_err() {
-        jtp::Error::MaxArrayEntriesExceeded { position, limit } => {
+        jtp::Error::MaxArrayEntriesExceeded {
+            position,
+            path,
+            limit,
+        } => {
             assert_eq!(
                 position,
                 Position {
@@ -106,6 +118,7 @@ fn limit_array() {
                 }
             );
             assert_eq!(limit, 4);
+            assert_eq!(path, "/4");
         }
         _ => panic!("unexpected error"),
     }
@@ -120,7 +133,11 @@ fn limit_array() {
         .validate();
     assert!(result.is_err());
     match result.unwrap_err() {
-        jtp::Error::MaxArrayEntriesExceeded { position, limit } => {
+        jtp::Error::MaxArrayEntriesExceeded {
+            position,
+            path,
+            limit,
+        } => {
             assert_eq!(
                 position,
                 Position {
@@ -130,6 +147,7 @@ fn limit_array() {
                 }
             );
             assert_eq!(limit, 4);
+            assert_eq!(path, "/4");
         }
         _ => panic!("unexpected error"),
     }
@@ -153,7 +171,11 @@ fn limit_object_entries() {
     let result = jtp::from_slice(&v).with_max_object_entries(4).validate();
     assert!(result.is_err());
     match result.unwrap_err() {
-        jtp::Error::MaxObjectEntriesExceeded { position, limit } => {
+        jtp::Error::MaxObjectEntriesExceeded {
+            position,
+            path,
+            limit,
+        } => {
             assert_eq!(
                 position,
                 Position {
@@ -163,6 +185,7 @@ fn limit_object_entries() {
                 }
             );
             assert_eq!(limit, 4);
+            assert_eq!(path, "/key5");
         }
         _ => panic!("unexpected error"),
     }
@@ -177,7 +200,11 @@ fn limit_object_entries() {
         .validate();
     assert!(result.is_err());
     match result.unwrap_err() {
-        jtp::Error::MaxObjectEntriesExceeded { position, limit } => {
+        jtp::Error::MaxObjectEntriesExceeded {
+            position,
+            path,
+            limit,
+        } => {
             assert_eq!(
                 position,
                 Position {
@@ -187,6 +214,7 @@ fn limit_object_entries() {
                 }
             );
             assert_eq!(limit, 4);
+            assert_eq!(path, "/key5");
         }
         _ => panic!("unexpected error"),
     }
@@ -197,6 +225,72 @@ fn limit_object_entries() {
         .unwrap();
 }
 
+#[test]
+fn limit_nested_entries() {
+    // Nested arrays and objects are entries of their parent like any other value.
+    let v = r#"[[], {}, [1], {"a": 1}]"#;
+    for result in [
+        jtp::from_str(v).with_max_array_entries(3).validate(),
+        jtp::from_reader(v.as_bytes())
+            .with_max_array_entries(3)
+            .validate(),
+    ] {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(&err, jtp::Error::MaxArrayEntriesExceeded { path, limit: 3, .. } if path == "/3"),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+    jtp::from_str(v)
+        .with_max_array_entries(4)
+        .validate()
+        .unwrap();
+
+    let v = r#"{"a": [], "b": {}, "c": [{}]}"#;
+    for result in [
+        jtp::from_str(v).with_max_object_entries(2).validate(),
+        jtp::from_reader(v.as_bytes())
+            .with_max_object_entries(2)
+            .validate(),
+    ] {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(&err, jtp::Error::MaxObjectEntriesExceeded { path, limit: 2, .. } if path == "/c"),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+    jtp::from_str(v)
+        .with_max_object_entries(3)
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn entry_counted_before_value() {
+    // The entry is counted before its value is checked,
+    // so the violations of the value point at the entry.
+    let v = r#"["123456", "123456"]"#;
+    let errors = jtp::from_str(v)
+        .with_max_array_entries(1)
+        .with_max_string_length(5)
+        .validate_all()
+        .unwrap_err();
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|err| match err {
+            jtp::Error::MaxStringLengthExceeded { path, .. } => ("string", path.as_str()),
+            jtp::Error::MaxArrayEntriesExceeded { path, .. } => ("entries", path.as_str()),
+            err => panic!("unexpected error: {:?}", err),
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        [("string", "/0"), ("entries", "/1"), ("string", "/1")]
+    );
+}
+
 #[test]
 fn limit_object_entry_name_length() {
     let v = mkjson!({
@@ -211,7 +305,9 @@ fn limit_object_entry_name_length() {
     match result.unwrap_err() {
         jtp::Error::MaxObjectEntryNameLengthExceeded {
             position,
+            path,
             limit,
+            unit,
             name,
         } => {
             assert_eq!(
@@ -223,6 +319,8 @@ fn limit_object_entry_name_length() {
                 }
             );
             assert_eq!(limit, 5);
+            assert_eq!(unit, jtp::LengthUnit::Bytes);
+            assert_eq!(path, "/123456");
             assert_eq!(name, "123456");
         }
         _ => panic!("unexpected error"),
@@ -240,7 +338,9 @@ fn limit_object_entry_name_length() {
     match result.unwrap_err() {
         jtp::Error::MaxObjectEntryNameLengthExceeded {
             position,
+            path,
             limit,
+            unit,
             name,
         } => {
             assert_eq!(
@@ -252,6 +352,8 @@ fn limit_object_entry_name_length() {
                 }
             );
             assert_eq!(limit, 5);
+            assert_eq!(unit, jtp::LengthUnit::Bytes);
+            assert_eq!(path, "/123456");
             assert_eq!(name, "123456");
         }
         _ => panic!("unexpected error"),
@@ -272,7 +374,12 @@ fn disallow_duplicate_object_entry_name() {
         .validate();
     assert!(result.is_err());
     match result.unwrap_err() {
-        jtp::Error::DuplicateObjectEntryName { position, key } => {
+        jtp::Error::DuplicateObjectEntryName {
+            position,
+            path,
+            key,
+            earlier_key,
+        } => {
             assert_eq!(
                 position,
                 Position {
@@ -282,6 +389,8 @@ fn disallow_duplicate_object_entry_name() {
                 }
             );
             assert_eq!(key, "key");
+            assert_eq!(earlier_key.as_deref(), Some("key"));
+            assert_eq!(path, "/key");
         }
         _ => panic!("unexpected error"),
     }
@@ -293,7 +402,12 @@ fn disallow_duplicate_object_entry_name() {
         .validate();
     assert!(result.is_err());
     match result.unwrap_err() {
-        jtp::Error::DuplicateObjectEntryName { position, key } => {
+        jtp::Error::DuplicateObjectEntryName {
+            position,
+            path,
+            key,
+            earlier_key,
+        } => {
             assert_eq!(
                 position,
                 Position {
@@ -303,6 +417,9 @@ fn disallow_duplicate_object_entry_name() {
                 }
             );
             assert_eq!(key, "key");
+            // the reader could not look back at the earlier name
+            assert_eq!(earlier_key, None);
+            assert_eq!(path, "/key");
         }
         _ => panic!("unexpected error"),
     }
@@ -310,6 +427,22 @@ fn disallow_duplicate_object_entry_name() {
     jtp::from_reader(std::io::BufReader::new(v.as_bytes()))
         .validate()
         .unwrap();
+
+    // The earlier name is only available while its chunk is retained.
+    for (chunk, expected) in [(v.len(), Some("key")), (9, None)] {
+        let mut validator = jtp::from_chunks().disallow_duplicate_object_entry_name();
+        let result = v
+            .as_bytes()
+            .chunks(chunk)
+            .try_for_each(|chunk| validator.feed(chunk).map(|_| ()))
+            .and_then(|_| validator.finish());
+        match result.unwrap_err() {
+            jtp::Error::DuplicateObjectEntryName { earlier_key, .. } => {
+                assert_eq!(earlier_key.as_deref(), expected, "chunks of {}", chunk);
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
 }
 
 #[test]
@@ -319,7 +452,11 @@ fn limit_depth() {
     let result = jtp::from_str(v).with_max_depth(4).validate();
     assert!(result.is_err());
     match result.unwrap_err() {
-        jtp::Error::MaxDepthExceeded { position, limit } => {
+        jtp::Error::MaxDepthExceeded {
+            position,
+            path,
+            limit,
+        } => {
             assert_eq!(
                 position,
                 Position {
@@ -329,6 +466,7 @@ fn limit_depth() {
                 }
             );
             assert_eq!(limit, 4);
+            assert_eq!(path, "/key/key/key/key");
         }
         _ => panic!("unexpected error"),
     }
@@ -340,7 +478,11 @@ fn limit_depth() {
         .validate();
     assert!(result.is_err());
     match result.unwrap_err() {
-        jtp::Error::MaxDepthExceeded { position, limit } => {
+        jtp::Error::MaxDepthExceeded {
+            position,
+            path,
+            limit,
+        } => {
             assert_eq!(
                 position,
                 Position {
@@ -350,6 +492,7 @@ fn limit_depth() {
                 }
             );
             assert_eq!(limit, 4);
+            assert_eq!(path, "/key/key/key/key");
         }
         _ => panic!("unexpected error"),
     }
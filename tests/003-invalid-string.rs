@@ -1,5 +1,10 @@
 mod utils;
-use json_threat_protection::{self as jtp, read::Position, Error, LexerError, ReadError};
+use json_threat_protection::{
+    self as jtp,
+    event::{Event, EventReader},
+    read::{Position, Read},
+    Error, LexerError, ReadError,
+};
 
 #[test]
 fn invalid_utf8_sequence() {
@@ -27,11 +32,11 @@ fn invalid_utf8_sequence() {
     assert!(
         matches!(
             err,
-            Error::LexerError(LexerError::ReadError(ReadError::InvalidEscapeSequence(
+            Error::LexerError(LexerError::ReadError(ReadError::LoneHighSurrogate(
                 Position {
                     line: 1,
-                    column: 16,
-                    offset: 16
+                    column: 15,
+                    offset: 15
                 }
             )))
         ),
@@ -154,3 +159,42 @@ fn invalid_incomplete_escape() {
         err
     );
 }
+
+fn decoded_strings<R: Read>(mut events: EventReader<R>) -> Vec<String> {
+    let mut strings = Vec::new();
+    while let Some((event, _)) = events.next_event().unwrap() {
+        if let Event::String(str) = event {
+            strings.push(str.to_string());
+        }
+    }
+    strings
+}
+
+#[test]
+fn surrogate_pairs_above_d83f() {
+    // The high surrogates above `\uD83F` overflowed `u16` once shifted.
+    let v = r#"["\ud83d\ude00", "\ud840\udc00", "\udbff\udfff"]"#;
+    let expected = ["\u{1f600}", "\u{20000}", "\u{10ffff}"];
+
+    assert_eq!(decoded_strings(jtp::from_str(v).into_events()), expected);
+    assert_eq!(
+        decoded_strings(jtp::from_slice(v.as_bytes()).into_events()),
+        expected
+    );
+    assert_eq!(
+        decoded_strings(jtp::from_reader(v.as_bytes()).into_events()),
+        expected
+    );
+
+    // The escaped name is the same as the literal one.
+    let v = "{\"\\udbff\\udfff\": 1, \"\u{10ffff}\": 2}";
+    let err = jtp::from_str(v)
+        .disallow_duplicate_object_entry_name()
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::DuplicateObjectEntryName { .. }),
+        "unexpected error: {:?}",
+        err
+    );
+}
@@ -0,0 +1,139 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, read::Position};
+use std::io::Read;
+
+#[test]
+fn limit_payload_size() {
+    let v = r#"{"key": [1, 2, 3]}"#;
+
+    let err = jtp::from_str(v)
+        .with_max_payload_bytes(10)
+        .validate()
+        .unwrap_err();
+    match err {
+        jtp::Error::MaxPayloadSizeExceeded {
+            position,
+            path,
+            limit,
+        } => {
+            assert_eq!(
+                position,
+                Position {
+                    line: 1,
+                    column: 11,
+                    offset: 11,
+                }
+            );
+            assert_eq!(limit, 10);
+            assert_eq!(path, "/key/0");
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+
+    let err = jtp::from_slice(v.as_bytes())
+        .with_max_payload_bytes(10)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxPayloadSizeExceeded { limit: 10, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+
+    let err = jtp::from_reader(v.as_bytes())
+        .with_max_payload_bytes(10)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxPayloadSizeExceeded { limit: 10, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+
+    jtp::from_str(v)
+        .with_max_payload_bytes(v.len())
+        .validate()
+        .unwrap();
+    jtp::from_reader(v.as_bytes())
+        .with_max_payload_bytes(v.len())
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn limit_payload_size_with_trailing_whitespace() {
+    // The JSON itself fits in the limit, but the whitespace after it does not.
+    let v = "1        ";
+
+    let err = jtp::from_str(v)
+        .with_max_payload_bytes(4)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxPayloadSizeExceeded { limit: 4, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+
+    let err = jtp::from_reader(v.as_bytes())
+        .with_max_payload_bytes(4)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxPayloadSizeExceeded { limit: 4, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+}
+
+#[test]
+fn limit_payload_size_with_steps() {
+    let v = r#"[1, 2, 3, 4, 5, 6, 7, 8, 9]"#;
+
+    let mut validator = jtp::from_str(v).with_max_payload_bytes(8);
+    let err = loop {
+        match validator.validate_with_steps(1) {
+            Ok(false) => continue,
+            Ok(true) => panic!("validation should fail"),
+            Err(err) => break err,
+        }
+    };
+    assert!(
+        matches!(err, jtp::Error::MaxPayloadSizeExceeded { limit: 8, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+}
+
+#[test]
+fn limit_payload_size_on_endless_stream() {
+    let endless_whitespace = b"[".chain(std::io::repeat(b' '));
+    let err = jtp::from_reader(endless_whitespace)
+        .with_max_payload_bytes(1024)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::MaxPayloadSizeExceeded {
+                position: Position { offset: 1025, .. },
+                limit: 1024,
+                ..
+            }
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+
+    let endless_string = b"\"".chain(std::io::repeat(b'a'));
+    let err = jtp::from_reader(endless_string)
+        .with_max_payload_bytes(1024)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxPayloadSizeExceeded { limit: 1024, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+}
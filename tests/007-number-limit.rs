@@ -0,0 +1,237 @@
+mod utils;
+
+use json_threat_protection::{
+    self as jtp,
+    read::{FeedRead, Position},
+    NumberPolicy,
+};
+use std::io::Read;
+
+#[test]
+fn limit_number_length() {
+    let v = r#"[-12.5e+3]"#;
+
+    let err = jtp::from_str(v)
+        .with_max_number_length(7)
+        .validate()
+        .unwrap_err();
+    match err {
+        jtp::Error::MaxNumberLengthExceeded {
+            position,
+            path,
+            limit,
+        } => {
+            assert_eq!(
+                position,
+                Position {
+                    line: 1,
+                    column: 9,
+                    offset: 9,
+                }
+            );
+            assert_eq!(limit, 7);
+            assert_eq!(path, "/0");
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+
+    jtp::from_str(v)
+        .with_max_number_length(8)
+        .validate()
+        .unwrap();
+
+    let err = jtp::from_reader(v.as_bytes())
+        .with_max_number_length(7)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxNumberLengthExceeded { limit: 7, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+
+    jtp::from_reader(v.as_bytes())
+        .with_max_number_length(8)
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn limit_number_digits() {
+    let v = r#"{"key": -123.4567e-89}"#;
+
+    let err = jtp::from_str(v)
+        .with_max_number_integer_digits(2)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            // The number is rejected at the first digit over the limit.
+            jtp::Error::MaxNumberIntegerDigitsExceeded {
+                position: Position {
+                    line: 1,
+                    column: 12,
+                    offset: 12
+                },
+                limit: 2,
+                ..
+            }
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+
+    let err = jtp::from_str(v)
+        .with_max_number_fraction_digits(3)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::MaxNumberFractionDigitsExceeded { limit: 3, .. }
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+
+    let err = jtp::from_reader(v.as_bytes())
+        .with_max_number_exponent_digits(1)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::MaxNumberExponentDigitsExceeded { limit: 1, .. }
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+
+    jtp::from_str(v)
+        .with_max_number_integer_digits(3)
+        .with_max_number_fraction_digits(4)
+        .with_max_number_exponent_digits(2)
+        .validate()
+        .unwrap();
+
+    jtp::from_str("0")
+        .with_max_number_integer_digits(1)
+        .with_max_number_fraction_digits(0)
+        .with_max_number_exponent_digits(0)
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn limit_exponent_magnitude() {
+    let v = r#"[1e999999999]"#;
+
+    let err = jtp::from_str(v)
+        .with_max_exponent_magnitude(308)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::MaxExponentMagnitudeExceeded {
+                position: Position {
+                    line: 1,
+                    column: 6,
+                    offset: 6
+                },
+                limit: 308,
+                ..
+            }
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+
+    let err = jtp::from_reader(r#"[1E-309]"#.as_bytes())
+        .with_max_exponent_magnitude(308)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::MaxExponentMagnitudeExceeded { limit: 308, .. }
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+
+    // Leading zeros in the exponent do not contribute to the magnitude.
+    jtp::from_str(r#"[1e+000000000308, 1.5, 10]"#)
+        .with_max_exponent_magnitude(308)
+        .validate()
+        .unwrap();
+
+    // The magnitude saturates instead of overflowing.
+    let err = jtp::from_str(r#"1e99999999999999999999999999999999999999"#)
+        .with_max_exponent_magnitude(usize::MAX - 1)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxExponentMagnitudeExceeded { .. }),
+        "unexpected error: {:?}",
+        err
+    );
+}
+
+#[test]
+fn rejected_before_end_of_number() {
+    let mut validator = jtp::Validator::new(FeedRead::new()).with_max_number_length(10);
+    let err = validator.feed(b"[1, 123456789012").unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxNumberLengthExceeded { limit: 10, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+    assert_eq!(err.path(), Some("/1"));
+
+    // The digits never end, so the number must be rejected while it is read.
+    let endless = b"[0.".chain(std::io::repeat(b'1'));
+    let err = jtp::from_reader(endless)
+        .with_max_number_fraction_digits(1000)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::MaxNumberFractionDigitsExceeded {
+                position: Position { offset: 1004, .. },
+                limit: 1000,
+                ..
+            }
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+}
+
+#[test]
+fn oversized_number_is_not_buffered() {
+    let v = format!("[{}]", "1".repeat(1024 * 1024));
+
+    // Without a policy, a rule or the events, the literal is never buffered.
+    jtp::from_str(&v)
+        .with_max_validator_memory(64 * 1024)
+        .validate()
+        .unwrap();
+    jtp::from_reader(v.as_bytes())
+        .with_max_validator_memory(64 * 1024)
+        .validate()
+        .unwrap();
+
+    let err = jtp::from_str(&v)
+        .with_max_validator_memory(64 * 1024)
+        .with_number_policy(NumberPolicy::IntegerOnly)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxValidatorMemoryExceeded { .. }),
+        "unexpected error: {:?}",
+        err
+    );
+}
@@ -0,0 +1,92 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, read::Position, NumberPolicy};
+
+fn accepts(v: &str, policy: NumberPolicy) -> bool {
+    let result = jtp::from_str(v).with_number_policy(policy).validate();
+    let reader_result = jtp::from_reader(v.as_bytes())
+        .with_number_policy(policy)
+        .validate();
+    assert_eq!(result.is_ok(), reader_result.is_ok());
+
+    match result {
+        Ok(()) => true,
+        Err(jtp::Error::NumberPolicyViolation { policy: p, .. }) if p == policy => false,
+        Err(err) => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn arbitrary_precision() {
+    let policy = NumberPolicy::ArbitraryPrecision;
+    assert!(accepts("123456789012345678901234567890", policy));
+    assert!(accepts("3.14159265358979323846264338327950288", policy));
+    assert!(accepts("1e999999999", policy));
+}
+
+#[test]
+fn integer_only() {
+    let policy = NumberPolicy::IntegerOnly;
+    assert!(accepts("[0, -1, 123456789012345678901234567890]", policy));
+    assert!(!accepts("1.0", policy));
+    assert!(!accepts("1e2", policy));
+}
+
+#[test]
+fn fits_in_i64() {
+    let policy = NumberPolicy::I64;
+    assert!(accepts(
+        "[0, -9223372036854775808, 9223372036854775807]",
+        policy
+    ));
+    assert!(!accepts("9223372036854775808", policy));
+    assert!(!accepts("-9223372036854775809", policy));
+    assert!(!accepts("1.5", policy));
+}
+
+#[test]
+fn fits_in_u64() {
+    let policy = NumberPolicy::U64;
+    assert!(accepts("[0, 18446744073709551615]", policy));
+    assert!(!accepts("18446744073709551616", policy));
+    assert!(!accepts("-1", policy));
+    assert!(!accepts("1e2", policy));
+}
+
+#[test]
+fn round_trip_through_f64() {
+    let policy = NumberPolicy::F64;
+    assert!(accepts(
+        "[0, -0, 0.1, 1.5e300, -2.5E-3, 100, 1.0, 0.000100e2, 9007199254740992]",
+        policy
+    ));
+    assert!(!accepts("3.14159265358979323846", policy));
+    assert!(!accepts("9007199254740993", policy));
+    assert!(!accepts("1e400", policy));
+    assert!(!accepts("1e-400", policy));
+}
+
+#[test]
+fn position_of_violation() {
+    let err = jtp::from_str(r#"{"key": 1.5}"#)
+        .with_number_policy(NumberPolicy::IntegerOnly)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::NumberPolicyViolation {
+                position: Position {
+                    line: 1,
+                    column: 11,
+                    offset: 11
+                },
+                policy: NumberPolicy::IntegerOnly,
+                ..
+            }
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+    assert_eq!(err.path(), Some("/key"));
+}
@@ -0,0 +1,244 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, read::Position, KeyCharset, KeyPattern, KeyPolicy};
+
+#[test]
+fn collect_all_errors() {
+    let v = r#"{"name": "too long string", "tags": [1, 2, 3, 4], "nested": {"name": "another long string"}}"#;
+
+    let errors = jtp::from_str(v)
+        .with_max_string_length(8)
+        .with_max_array_entries(2)
+        .validate_all()
+        .unwrap_err();
+    assert_eq!(errors.len(), 3, "unexpected errors: {:?}", errors);
+
+    match &errors[0] {
+        jtp::Error::MaxStringLengthExceeded {
+            position,
+            path,
+            limit,
+            unit,
+            str,
+        } => {
+            assert_eq!(
+                *position,
+                Position {
+                    line: 1,
+                    column: 26,
+                    offset: 26,
+                }
+            );
+            assert_eq!(*limit, 8);
+            assert_eq!(*unit, jtp::LengthUnit::Bytes);
+            assert_eq!(str, "too long string");
+            assert_eq!(path, "/name");
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+    assert!(
+        matches!(
+            errors[1],
+            jtp::Error::MaxArrayEntriesExceeded {
+                position: Position {
+                    line: 1,
+                    column: 44,
+                    offset: 44
+                },
+                limit: 2,
+                ..
+            }
+        ),
+        "unexpected error: {:?}",
+        errors[1]
+    );
+    assert!(
+        matches!(
+            &errors[2],
+            jtp::Error::MaxStringLengthExceeded { str, .. } if str == "another long string"
+        ),
+        "unexpected error: {:?}",
+        errors[2]
+    );
+
+    let reader_errors = jtp::from_reader(v.as_bytes())
+        .with_max_string_length(8)
+        .with_max_array_entries(2)
+        .validate_all()
+        .unwrap_err();
+    assert_eq!(reader_errors.len(), 3);
+
+    jtp::from_str(v).validate_all().unwrap();
+}
+
+#[test]
+fn collect_errors_then_fatal() {
+    let v = r#"[[1], "too long string", 3"#;
+
+    let errors = jtp::from_str(v)
+        .with_max_string_length(8)
+        .validate_all()
+        .unwrap_err();
+    assert_eq!(errors.len(), 2, "unexpected errors: {:?}", errors);
+    assert!(matches!(
+        errors[0],
+        jtp::Error::MaxStringLengthExceeded { limit: 8, .. }
+    ));
+    assert!(matches!(errors[1], jtp::Error::InvalidJSON(_)));
+}
+
+#[test]
+fn max_depth_is_fatal() {
+    let v = r#"["too long string", [[[[[[[[1]]]]]]]], "another long string"]"#;
+
+    let errors = jtp::from_str(v)
+        .with_max_depth(2)
+        .with_max_string_length(8)
+        .validate_all()
+        .unwrap_err();
+    assert_eq!(errors.len(), 2, "unexpected errors: {:?}", errors);
+    assert!(matches!(
+        errors[0],
+        jtp::Error::MaxStringLengthExceeded { limit: 8, .. }
+    ));
+    assert!(
+        matches!(
+            errors[1],
+            jtp::Error::MaxDepthExceeded {
+                position: Position { offset: 22, .. },
+                limit: 2,
+                ..
+            }
+        ),
+        "unexpected error: {:?}",
+        errors[1]
+    );
+}
+
+#[test]
+fn limit_collected_errors() {
+    let v = r#"{"a": 1, "a": 2, "a": 3, "a": 4}"#;
+
+    let errors = jtp::from_str(v)
+        .disallow_duplicate_object_entry_name()
+        .with_max_errors(2)
+        .validate_all()
+        .unwrap_err();
+    assert_eq!(errors.len(), 2, "unexpected errors: {:?}", errors);
+    assert!(errors
+        .iter()
+        .all(|err| matches!(err, jtp::Error::DuplicateObjectEntryName { .. })));
+
+    let errors = jtp::from_str(v)
+        .disallow_duplicate_object_entry_name()
+        .validate_all()
+        .unwrap_err();
+    assert_eq!(errors.len(), 3, "unexpected errors: {:?}", errors);
+}
+
+#[test]
+fn default_limit_collected_errors() {
+    let v = format!("[{}]", vec!["\"too long string\""; 200].join(","));
+
+    let errors = jtp::from_str(&v)
+        .with_max_string_length(8)
+        .validate_all()
+        .unwrap_err();
+    assert_eq!(errors.len(), 100);
+
+    for max_errors in [0, 1] {
+        let errors = jtp::from_str(&v)
+            .with_max_string_length(8)
+            .with_max_errors(max_errors)
+            .validate_all()
+            .unwrap_err();
+        assert_eq!(errors.len(), 1, "max_errors: {}", max_errors);
+    }
+}
+
+#[test]
+fn collected_strings_are_truncated() {
+    let long = "é".repeat(100);
+    let v = format!(r#"{{"{}": 1, "a": ["{}"]}}"#, long, long);
+
+    let errors = jtp::from_str(&v)
+        .with_max_string_length(8)
+        .with_max_object_entry_name_length(8)
+        .validate_all()
+        .unwrap_err();
+    assert_eq!(errors.len(), 2, "unexpected errors: {:?}", errors);
+    assert!(
+        matches!(&errors[0], jtp::Error::MaxObjectEntryNameLengthExceeded { name, .. } if *name == long[..64])
+    );
+    assert!(
+        matches!(&errors[1], jtp::Error::MaxStringLengthExceeded { str, path, .. } if *str == long[..64] && path == "/a/0")
+    );
+
+    let err = jtp::from_str(&v)
+        .with_max_string_length(8)
+        .validate()
+        .unwrap_err();
+    assert!(matches!(err, jtp::Error::MaxStringLengthExceeded { str, .. } if str == long));
+}
+
+#[test]
+fn paths_of_collected_errors() {
+    let v = r#"{"a": [{"b": ["too long string", 1, "too long string"]}, {"c/~": "too long string"}], "d": "too long string"}"#;
+
+    let paths: Vec<String> = jtp::from_str(v)
+        .with_max_string_length(8)
+        .validate_all()
+        .unwrap_err()
+        .into_iter()
+        .map(|err| match err {
+            jtp::Error::MaxStringLengthExceeded { path, .. } => path,
+            err => panic!("unexpected error: {:?}", err),
+        })
+        .collect();
+    assert_eq!(paths, ["/a/0/b/0", "/a/0/b/2", "/a/1/c~1~0", "/d"]);
+}
+
+#[test]
+fn collected_names_are_truncated() {
+    let long = "é".repeat(500);
+    let v = format!(
+        r#"{{"{long}": 1, "{long}": 2, "${long}": 3, "{long}.": 4}}"#,
+        long = long
+    );
+
+    let errors = jtp::from_str(&v)
+        .disallow_duplicate_object_entry_name()
+        .with_key_policy(KeyPolicy::deny(vec![KeyPattern::Prefix("$".to_string())]))
+        .with_key_charset(KeyCharset {
+            allow_dots: false,
+            ..Default::default()
+        })
+        .validate_all()
+        .unwrap_err();
+    assert_eq!(errors.len(), 3, "unexpected errors: {:?}", errors);
+
+    match &errors[0] {
+        jtp::Error::DuplicateObjectEntryName {
+            key, earlier_key, ..
+        } => {
+            assert_eq!(*key, long[..64]);
+            assert_eq!(earlier_key.as_deref(), Some(&long[..64]));
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+    assert!(
+        matches!(&errors[1], jtp::Error::ForbiddenObjectEntryName { name, .. } if name.len() <= 64 && long.starts_with(&name[1..]))
+    );
+    assert!(
+        matches!(&errors[2], jtp::Error::ForbiddenCharacterInObjectEntryName { name, .. } if *name == long[..64])
+    );
+
+    for err in &errors {
+        let path = err.path().unwrap();
+        assert!(
+            path.len() <= 256 && path.len() > 64,
+            "unexpected path: {:?}",
+            path
+        );
+    }
+}
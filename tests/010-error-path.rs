@@ -0,0 +1,65 @@
+mod utils;
+
+use json_threat_protection as jtp;
+
+fn path_of(v: &str, validator: impl Fn(&str) -> jtp::Validator<jtp::read::StrRead>) -> String {
+    let err = validator(v).validate().unwrap_err();
+    err.path().expect("error should carry a path").to_string()
+}
+
+#[test]
+fn path_of_nested_value() {
+    let v = r#"{"users": [{"name": "a"}, {"name": "b"}, {"name": "c"}, {"name": "too long"}]}"#;
+
+    let path = path_of(v, |v| jtp::from_str(v).with_max_string_length(4));
+    assert_eq!(path, "/users/3/name");
+
+    let err = jtp::from_reader(v.as_bytes())
+        .with_max_string_length(4)
+        .validate()
+        .unwrap_err();
+    assert_eq!(err.path(), Some("/users/3/name"));
+
+    let err = jtp::from_slice(v.as_bytes())
+        .with_max_string_length(4)
+        .validate()
+        .unwrap_err();
+    assert_eq!(err.path(), Some("/users/3/name"));
+}
+
+#[test]
+fn path_escaping() {
+    let v = r#"{"a/b": {"m~n": "too long"}}"#;
+    let path = path_of(v, |v| jtp::from_str(v).with_max_string_length(4));
+    assert_eq!(path, "/a~1b/m~0n");
+
+    let v = r#"{"": ["too long"]}"#;
+    let path = path_of(v, |v| jtp::from_str(v).with_max_string_length(4));
+    assert_eq!(path, "//0");
+}
+
+#[test]
+fn path_of_containers() {
+    let v = r#"{"key": [[], [], []]}"#;
+    let path = path_of(v, |v| jtp::from_str(v).with_max_array_entries(2));
+    assert_eq!(path, "/key/2");
+
+    let v = r#"{"a": {}, "b": {}, "c": {}}"#;
+    let path = path_of(v, |v| jtp::from_str(v).with_max_object_entries(2));
+    assert_eq!(path, "/c");
+
+    let v = r#"[{"key": [[1]]}]"#;
+    let path = path_of(v, |v| jtp::from_str(v).with_max_depth(3));
+    assert_eq!(path, "/0/key/0");
+}
+
+#[test]
+fn path_of_root() {
+    let path = path_of(r#""too long""#, |v| {
+        jtp::from_str(v).with_max_string_length(4)
+    });
+    assert_eq!(path, "");
+
+    let err = jtp::from_str("[1, 2").validate().unwrap_err();
+    assert_eq!(err.path(), None);
+}
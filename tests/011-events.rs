@@ -0,0 +1,110 @@
+mod utils;
+
+use json_threat_protection::{
+    self as jtp,
+    event::{Event, EventReader},
+    read::{Position, Read},
+};
+
+fn collect<R: Read>(mut events: EventReader<R>) -> Result<Vec<String>, jtp::Error> {
+    let mut collected = Vec::new();
+    while let Some((event, _)) = events.next_event()? {
+        collected.push(format!("{:?}", event));
+    }
+    Ok(collected)
+}
+
+#[test]
+fn read_events() {
+    let v =
+        r#"{"a": [1, -2.5e3, "s\n"], "b": {"c": true, "d": false}, "e": null, "f": [], "g": {}}"#;
+    let expected = vec![
+        "StartObject",
+        "Key(\"a\")",
+        "StartArray",
+        "Number(\"1\")",
+        "Number(\"-2.5e3\")",
+        "String(\"s\\n\")",
+        "EndArray",
+        "Key(\"b\")",
+        "StartObject",
+        "Key(\"c\")",
+        "Bool(true)",
+        "Key(\"d\")",
+        "Bool(false)",
+        "EndObject",
+        "Key(\"e\")",
+        "Null",
+        "Key(\"f\")",
+        "StartArray",
+        "EndArray",
+        "Key(\"g\")",
+        "StartObject",
+        "EndObject",
+        "EndObject",
+    ];
+
+    assert_eq!(collect(jtp::from_str(v).into_events()).unwrap(), expected);
+    assert_eq!(
+        collect(jtp::from_slice(v.as_bytes()).into_events()).unwrap(),
+        expected
+    );
+    assert_eq!(
+        collect(jtp::from_reader(v.as_bytes()).into_events()).unwrap(),
+        expected
+    );
+
+    assert_eq!(
+        collect(jtp::from_str(" 42 ").into_events()).unwrap(),
+        vec!["Number(\"42\")"]
+    );
+}
+
+#[test]
+fn event_positions_and_paths() {
+    let v = "{\"a\": [1,\n  \"x\"]}";
+    let mut events = jtp::from_str(v).into_events();
+    let mut collected = Vec::new();
+    while let Some((_, position)) = events.next_event().unwrap() {
+        collected.push((position, events.path()));
+    }
+
+    let at = |line, column, offset| Position {
+        line,
+        column,
+        offset,
+    };
+    assert_eq!(
+        collected,
+        vec![
+            (at(1, 0, 0), "".to_string()),
+            (at(1, 1, 1), "/a".to_string()),
+            (at(1, 6, 6), "/a".to_string()),
+            (at(1, 7, 7), "/a/0".to_string()),
+            (at(2, 2, 12), "/a/1".to_string()),
+            (at(2, 5, 15), "/a".to_string()),
+            (at(2, 6, 16), "".to_string()),
+        ]
+    );
+    assert!(events.next_event().unwrap().is_none());
+}
+
+#[test]
+fn events_are_validated() {
+    let v = r#"{"a": "too long string"}"#;
+    let mut events = jtp::from_str(v).with_max_string_length(4).into_events();
+    assert_eq!(events.next_event().unwrap().unwrap().0, Event::StartObject);
+    assert_eq!(events.next_event().unwrap().unwrap().0, Event::Key("a"));
+    let err = events.next_event().unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxStringLengthExceeded { limit: 4, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+    assert!(events.next_event().unwrap().is_none());
+
+    for v in [r#"[1, 2"#, r#"[1, 2]]"#, r#"{"a" 1}"#, r#"[1,]"#] {
+        let result = collect(jtp::from_str(v).into_events());
+        assert!(result.is_err(), "{} should be invalid", v);
+    }
+}
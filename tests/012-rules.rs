@@ -0,0 +1,209 @@
+mod utils;
+
+use json_threat_protection::{
+    self as jtp,
+    read::Position,
+    rule::{Context, Rule, RuleError},
+};
+
+struct ForbiddenKey(&'static str);
+
+impl Rule for ForbiddenKey {
+    fn on_key(&mut self, _ctx: &Context<'_>, key: &str) -> Result<(), RuleError> {
+        match key == self.0 {
+            true => Err(format!("forbidden key {}", key).into()),
+            false => Ok(()),
+        }
+    }
+}
+
+/// `amount` must be a number.
+struct AmountIsNumber;
+
+impl AmountIsNumber {
+    fn check(ctx: &Context<'_>) -> Result<(), RuleError> {
+        match ctx.key() {
+            Some("amount") => Err("amount must be a number".into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Rule for AmountIsNumber {
+    fn on_string(&mut self, ctx: &Context<'_>, _value: &str) -> Result<(), RuleError> {
+        Self::check(ctx)
+    }
+
+    fn on_bool(&mut self, ctx: &Context<'_>, _value: bool) -> Result<(), RuleError> {
+        Self::check(ctx)
+    }
+
+    fn on_null(&mut self, ctx: &Context<'_>) -> Result<(), RuleError> {
+        Self::check(ctx)
+    }
+
+    fn on_start_object(&mut self, ctx: &Context<'_>) -> Result<(), RuleError> {
+        Self::check(ctx)
+    }
+
+    fn on_start_array(&mut self, ctx: &Context<'_>) -> Result<(), RuleError> {
+        Self::check(ctx)
+    }
+}
+
+/// Records every callback with its path and depth.
+#[derive(Default)]
+struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+impl Recorder {
+    fn record(&self, ctx: &Context<'_>, what: String) -> Result<(), RuleError> {
+        let entry = format!("{} {} {}", what, ctx.path(), ctx.depth());
+        self.0.lock().unwrap().push(entry);
+        Ok(())
+    }
+}
+
+impl Rule for Recorder {
+    fn on_key(&mut self, ctx: &Context<'_>, key: &str) -> Result<(), RuleError> {
+        self.record(ctx, format!("key:{}", key))
+    }
+
+    fn on_string(&mut self, ctx: &Context<'_>, value: &str) -> Result<(), RuleError> {
+        self.record(ctx, format!("string:{}", value))
+    }
+
+    fn on_number(&mut self, ctx: &Context<'_>, literal: &str) -> Result<(), RuleError> {
+        self.record(ctx, format!("number:{}", literal))
+    }
+
+    fn on_bool(&mut self, ctx: &Context<'_>, value: bool) -> Result<(), RuleError> {
+        self.record(ctx, format!("bool:{}", value))
+    }
+
+    fn on_null(&mut self, ctx: &Context<'_>) -> Result<(), RuleError> {
+        self.record(ctx, "null".to_string())
+    }
+
+    fn on_start_object(&mut self, ctx: &Context<'_>) -> Result<(), RuleError> {
+        self.record(ctx, "{".to_string())
+    }
+
+    fn on_end_object(&mut self, ctx: &Context<'_>) -> Result<(), RuleError> {
+        self.record(ctx, "}".to_string())
+    }
+
+    fn on_start_array(&mut self, ctx: &Context<'_>) -> Result<(), RuleError> {
+        self.record(ctx, "[".to_string())
+    }
+
+    fn on_end_array(&mut self, ctx: &Context<'_>) -> Result<(), RuleError> {
+        self.record(ctx, "]".to_string())
+    }
+}
+
+#[test]
+fn reject_forbidden_key() {
+    let v = r#"{"filter": {"$where": "sleep(1000)"}}"#;
+
+    let err = jtp::from_str(v)
+        .with_rule(ForbiddenKey("$where"))
+        .validate()
+        .unwrap_err();
+    match err {
+        jtp::Error::Custom {
+            position,
+            path,
+            source,
+        } => {
+            assert_eq!(
+                position,
+                Position {
+                    line: 1,
+                    column: 20,
+                    offset: 20,
+                }
+            );
+            assert_eq!(path, "/filter/$where");
+            assert_eq!(source.to_string(), "forbidden key $where");
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+
+    let err = jtp::from_reader(v.as_bytes())
+        .with_rule(ForbiddenKey("$where"))
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::Custom { .. }),
+        "unexpected error: {:?}",
+        err
+    );
+
+    jtp::from_str(v)
+        .with_rule(ForbiddenKey("$regex"))
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn reject_by_value_type() {
+    jtp::from_str(r#"{"amount": 12.5, "name": "a", "items": [{"amount": 1}]}"#)
+        .with_rule(AmountIsNumber)
+        .validate()
+        .unwrap();
+
+    for v in [
+        r#"{"amount": "12.5"}"#,
+        r#"{"items": [{"amount": null}]}"#,
+        r#"{"amount": [1]}"#,
+    ] {
+        let err = jtp::from_str(v)
+            .with_rule(AmountIsNumber)
+            .validate()
+            .unwrap_err();
+        assert!(
+            matches!(&err, jtp::Error::Custom { source, .. } if source.to_string() == "amount must be a number"),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
+
+#[test]
+fn callbacks_with_path_and_depth() {
+    let recorder = Recorder::default();
+    let records = recorder.0.clone();
+
+    jtp::from_str(r#"{"a": [1, "x", true, null], "b": {}}"#)
+        .with_rule(recorder)
+        .validate()
+        .unwrap();
+
+    assert_eq!(
+        *records.lock().unwrap(),
+        vec![
+            "{  0",
+            "key:a /a 1",
+            "[ /a 1",
+            "number:1 /a/0 2",
+            "string:x /a/1 2",
+            "bool:true /a/2 2",
+            "null /a/3 2",
+            "] /a 1",
+            "key:b /b 1",
+            "{ /b 1",
+            "} /b 1",
+            "}  0",
+        ]
+    );
+}
+
+#[test]
+fn collect_custom_errors() {
+    let errors = jtp::from_str(r#"[{"$where": 1}, {"$where": 2}]"#)
+        .with_rule(ForbiddenKey("$where"))
+        .validate_all()
+        .unwrap_err();
+    let paths: Vec<_> = errors.iter().map(|err| err.path().unwrap()).collect();
+    assert_eq!(paths, vec!["/0/$where", "/1/$where"]);
+}
@@ -0,0 +1,155 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, NumberPolicy, ValidatorConfig};
+use std::sync::Arc;
+
+#[test]
+fn validate_with_config() {
+    let config = Arc::new(ValidatorConfig {
+        max_depth: 2,
+        max_payload_bytes: 64,
+        max_string_length: 4,
+        number_policy: NumberPolicy::I64,
+        allow_duplicate_object_entry_name: false,
+        ..Default::default()
+    });
+
+    jtp::from_str(r#"{"a": [1, "abc"]}"#)
+        .with_config(config.clone())
+        .validate()
+        .unwrap();
+
+    let cases = [
+        r#"[[[1]]]"#,
+        r#"["too long"]"#,
+        r#"[1.5]"#,
+        r#"{"a": 1, "a": 2}"#,
+        r#"[1,                                                               2]"#,
+    ];
+    for v in cases {
+        let result = jtp::from_str(v).with_config(config.clone()).validate();
+        assert!(result.is_err(), "{} should be rejected", v);
+
+        let result = jtp::from_reader(v.as_bytes())
+            .with_config(config.clone())
+            .validate();
+        assert!(result.is_err(), "{} should be rejected", v);
+    }
+
+    let err = jtp::from_str(cases[4])
+        .with_config(config.clone())
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxPayloadSizeExceeded { limit: 64, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+}
+
+#[test]
+fn config_replaces_previous_constraints() {
+    jtp::from_str(r#""too long""#)
+        .with_max_string_length(4)
+        .with_config(ValidatorConfig::default())
+        .validate()
+        .unwrap();
+
+    // The builder methods after `with_config` modify a copy of the shared config.
+    let config = Arc::new(ValidatorConfig::default());
+    let err = jtp::from_str(r#""too long""#)
+        .with_config(config.clone())
+        .with_max_string_length(4)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxStringLengthExceeded { limit: 4, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+    assert_eq!(*config, ValidatorConfig::default());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn deserialize_config() {
+    let config: ValidatorConfig = serde_json::from_str(
+        r#"{"max_depth": 8, "number_policy": "F64", "allow_duplicate_object_entry_name": false}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config,
+        ValidatorConfig {
+            max_depth: 8,
+            number_policy: NumberPolicy::F64,
+            allow_duplicate_object_entry_name: false,
+            ..Default::default()
+        }
+    );
+
+    let serialized = serde_json::to_string(&config).unwrap();
+    let deserialized: ValidatorConfig = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(config, deserialized);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn toml_round_trip() {
+    let configs = [
+        ValidatorConfig::default(),
+        ValidatorConfig {
+            max_depth: 8,
+            max_string_length: 1024,
+            number_policy: NumberPolicy::I64,
+            allow_duplicate_object_entry_name: false,
+            max_errors: jtp::NO_LIMIT,
+            ..Default::default()
+        },
+    ];
+
+    for config in configs {
+        let serialized = toml::to_string(&config).unwrap();
+        assert!(!serialized.contains("max_array_entries"), "{}", serialized);
+        let deserialized: ValidatorConfig = toml::from_str(&serialized).unwrap();
+        assert_eq!(config, deserialized, "{}", serialized);
+    }
+
+    let config: ValidatorConfig = toml::from_str("max_depth = 4\nmax_errors = 10").unwrap();
+    assert_eq!(config.max_depth, 4);
+    assert_eq!(config.max_errors, 10);
+    assert_eq!(config.max_payload_bytes, jtp::NO_LIMIT);
+}
+
+#[cfg(not(feature = "unicode-normalization"))]
+#[test]
+fn key_normalization_requires_feature() {
+    let config = Arc::new(ValidatorConfig {
+        key_normalization: jtp::KeyNormalization {
+            form: None,
+            case_fold: true,
+        },
+        ..Default::default()
+    });
+
+    let err = jtp::from_str(r#"{"a": 1}"#)
+        .with_config(config.clone())
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::FeatureNotEnabled {
+                option: "key_normalization",
+                feature: "unicode-normalization"
+            }
+        ),
+        "{:?}",
+        err
+    );
+
+    let mut validator = jtp::Validator::new(jtp::read::FeedRead::new()).with_config(config);
+    assert!(matches!(
+        validator.feed(b"[1]"),
+        Err(jtp::Error::FeatureNotEnabled { .. })
+    ));
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "apigee")]
+
+mod utils;
+
+use json_threat_protection::{
+    self as jtp,
+    apigee::{ApigeeError, Fault, Policy},
+    ValidatorConfig, NO_LIMIT,
+};
+
+const POLICY: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<!-- Protects the order API -->
+<JSONThreatProtection async="false" continueOnError="false" enabled="true" name="JSON-Threat-Protection-1">
+    <DisplayName>JSON Threat Protection &amp; More</DisplayName>
+    <Properties>
+        <Property name="unused">1</Property>
+    </Properties>
+    <ArrayElementCount>3</ArrayElementCount>
+    <ContainerDepth>2</ContainerDepth>
+    <ObjectEntryCount>-1</ObjectEntryCount>
+    <ObjectEntryNameLength>8</ObjectEntryNameLength>
+    <Source>request</Source>
+    <StringValueLength> 16 </StringValueLength>
+</JSONThreatProtection>
+"#;
+
+#[test]
+fn parse_policy() {
+    let policy = Policy::from_xml(POLICY).unwrap();
+    assert_eq!(policy.name.as_deref(), Some("JSON-Threat-Protection-1"));
+    assert_eq!(
+        policy.display_name.as_deref(),
+        Some("JSON Threat Protection & More")
+    );
+    assert_eq!(policy.source.as_deref(), Some("request"));
+    assert!(policy.enabled);
+    assert!(!policy.continue_on_error);
+    assert_eq!(
+        policy.config,
+        ValidatorConfig {
+            max_array_entries: 3,
+            max_depth: 2,
+            max_object_entries: NO_LIMIT,
+            max_object_entry_name_length: 8,
+            max_string_length: 16,
+            ..Default::default()
+        }
+    );
+
+    let policy = Policy::from_xml(
+        r#"<JSONThreatProtection enabled='false'><ContainerDepth/></JSONThreatProtection>"#,
+    )
+    .unwrap();
+    assert!(!policy.enabled);
+    assert_eq!(policy.config, ValidatorConfig::default());
+}
+
+#[test]
+fn reject_invalid_policy() {
+    assert_eq!(
+        Policy::from_xml("<XMLThreatProtection/>"),
+        Err(ApigeeError::UnexpectedRootElement(
+            "XMLThreatProtection".to_string()
+        ))
+    );
+    assert_eq!(
+        Policy::from_xml(
+            "<JSONThreatProtection><ContainerDepth>ten</ContainerDepth></JSONThreatProtection>"
+        ),
+        Err(ApigeeError::InvalidValue {
+            element: "ContainerDepth".to_string(),
+            value: "ten".to_string(),
+        })
+    );
+
+    for xml in [
+        "",
+        "<JSONThreatProtection>",
+        "<JSONThreatProtection><ContainerDepth>1</JSONThreatProtection>",
+        "<JSONThreatProtection/><JSONThreatProtection/>",
+        "<JSONThreatProtection name=unquoted/>",
+        "<JSONThreatProtection><!-- unclosed comment </JSONThreatProtection>",
+    ] {
+        assert!(
+            matches!(Policy::from_xml(xml), Err(ApigeeError::MalformedXml(_))),
+            "{} should be malformed",
+            xml
+        );
+    }
+}
+
+#[test]
+fn map_faults() {
+    let policy = Policy::from_xml(POLICY).unwrap();
+    let name = policy.name.as_deref().unwrap();
+
+    let execution_failed = "steps.jsonthreatprotection.ExecutionFailed";
+    let invalid_json = "steps.jsonthreatprotection.InvalidJSON";
+    let cases = [
+        (
+            "[1, 2, 3, 4]",
+            execution_failed,
+            "ExceededArrayElementCount",
+        ),
+        (r#"[[[1]]]"#, execution_failed, "ExceededContainerDepth"),
+        (
+            r#"{"too long name": 1}"#,
+            execution_failed,
+            "ExceededObjectEntryNameLength",
+        ),
+        (
+            r#"["too long string value"]"#,
+            execution_failed,
+            "ExceededStringValueLength",
+        ),
+        ("[1, 2", invalid_json, "InvalidJSON"),
+        ("[1, 2] 3", invalid_json, "InvalidJSON"),
+        ("[01]", invalid_json, "InvalidJSON"),
+        ("[tru]", invalid_json, "InvalidJSON"),
+    ];
+    for (v, code, fault_name) in cases {
+        let err = jtp::from_str(v)
+            .with_config(policy.config.clone())
+            .validate()
+            .unwrap_err();
+        let fault = Fault::new(name, &err);
+        assert_eq!(fault.code, code, "unexpected fault for {}", v);
+        assert_eq!(fault.name, fault_name, "unexpected fault for {}", v);
+        assert_eq!(fault.status, 500);
+    }
+
+    let err = jtp::from_str("{\n\"a\": {},\n\"b\": {}\n}")
+        .with_max_object_entries(1)
+        .validate()
+        .unwrap_err();
+    let fault = Fault::new("JTP", &err);
+    assert_eq!(fault.name, "ExceededObjectEntryCount");
+    assert_eq!(
+        fault.message,
+        "JSONThreatProtection[JTP]: Execution failed. reason: JSONThreatProtection[JTP]: Exceeded object entry count at line 3"
+    );
+    assert_eq!(
+        fault.to_json(),
+        r#"{"fault":{"faultstring":"JSONThreatProtection[JTP]: Execution failed. reason: JSONThreatProtection[JTP]: Exceeded object entry count at line 3","detail":{"errorcode":"steps.jsonthreatprotection.ExecutionFailed"}}}"#
+    );
+}
@@ -0,0 +1,104 @@
+mod utils;
+
+use json_threat_protection as jtp;
+
+/// Bytes retained for each object entry name on 64-bit platforms.
+const KEY_ENTRY_BYTES: usize = 24;
+
+fn assert_duplicate(v: &str, path: &str) {
+    let results = [
+        jtp::from_str(v)
+            .disallow_duplicate_object_entry_name()
+            .validate(),
+        jtp::from_slice(v.as_bytes())
+            .disallow_duplicate_object_entry_name()
+            .validate(),
+        jtp::from_reader(v.as_bytes())
+            .disallow_duplicate_object_entry_name()
+            .validate(),
+    ];
+
+    for result in results {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, jtp::Error::DuplicateObjectEntryName { .. }),
+            "unexpected error: {:?}",
+            err
+        );
+        assert_eq!(err.path(), Some(path));
+    }
+}
+
+#[test]
+fn detect_duplicates() {
+    assert_duplicate(r#"{"a": 1, "b": 2, "a": 3}"#, "/a");
+    assert_duplicate(r#"{"a": 1, "a": 2}"#, "/a");
+    assert_duplicate(r#"{"a": {"a": 1, "b": 2}, "b": {"b": 1, "b": 2}}"#, "/b/b");
+
+    jtp::from_str(r#"{"a": {"a": 1}, "b": [{"a": 1}, {"a": 2}]}"#)
+        .disallow_duplicate_object_entry_name()
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn retain_fixed_bytes_per_key() {
+    // Long names take the same bytes as short names.
+    let keys: Vec<String> = (0..1000)
+        .map(|i| format!(r#""{}{}": {}"#, "k".repeat(1000), i, i))
+        .collect();
+    let v = format!("{{{}}}", keys.join(", "));
+
+    jtp::from_str(&v)
+        .disallow_duplicate_object_entry_name()
+        .with_max_duplicate_detection_bytes(1000 * KEY_ENTRY_BYTES)
+        .validate()
+        .unwrap();
+
+    let err = jtp::from_reader(v.as_bytes())
+        .disallow_duplicate_object_entry_name()
+        .with_max_duplicate_detection_bytes(1000 * KEY_ENTRY_BYTES - 1)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::MaxDuplicateDetectionBytesExceeded { limit, .. } if limit == 1000 * KEY_ENTRY_BYTES - 1
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+    assert_eq!(
+        err.path(),
+        Some(format!("/{}999", "k".repeat(1000)).as_str())
+    );
+}
+
+#[test]
+fn release_bytes_of_closed_objects() {
+    let limit = 2 * KEY_ENTRY_BYTES;
+
+    jtp::from_str(r#"[{"a": 1, "b": 2}, {"c": 3, "d": 4}]"#)
+        .disallow_duplicate_object_entry_name()
+        .with_max_duplicate_detection_bytes(limit)
+        .validate()
+        .unwrap();
+
+    let err = jtp::from_str(r#"{"a": {"b": 1, "c": 2}}"#)
+        .disallow_duplicate_object_entry_name()
+        .with_max_duplicate_detection_bytes(limit)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxDuplicateDetectionBytesExceeded { .. }),
+        "unexpected error: {:?}",
+        err
+    );
+    assert_eq!(err.path(), Some("/a/c"));
+
+    // The limit does not take effect if duplicates are allowed.
+    jtp::from_str(r#"{"a": 1, "b": 2, "c": 3}"#)
+        .with_max_duplicate_detection_bytes(limit)
+        .validate()
+        .unwrap();
+}
@@ -0,0 +1,177 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, NumberPolicy};
+
+fn assert_exceeded(v: &str, limit: usize) {
+    let results = [
+        jtp::from_str(v).with_max_validator_memory(limit).validate(),
+        jtp::from_slice(v.as_bytes())
+            .with_max_validator_memory(limit)
+            .validate(),
+        jtp::from_reader(v.as_bytes())
+            .with_max_validator_memory(limit)
+            .validate(),
+    ];
+
+    for result in results {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, jtp::Error::MaxValidatorMemoryExceeded { limit: l, .. } if l == limit),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
+
+fn assert_ok(v: &str, limit: usize) {
+    jtp::from_str(v)
+        .with_max_validator_memory(limit)
+        .validate()
+        .unwrap();
+    jtp::from_slice(v.as_bytes())
+        .with_max_validator_memory(limit)
+        .validate()
+        .unwrap();
+    jtp::from_reader(v.as_bytes())
+        .with_max_validator_memory(limit)
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn small_document() {
+    assert_ok(r#"{"a": [1, 2, {"b": "c"}], "d": null}"#, 64 * 1024);
+}
+
+#[test]
+fn deep_nesting() {
+    let v = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+    assert_exceeded(&v, 64 * 1024);
+
+    let v = format!("{}{}", r#"{"a":"#.repeat(100_000), "}".repeat(100_000));
+    assert_exceeded(&v, 64 * 1024);
+}
+
+#[test]
+fn many_keys() {
+    let keys: Vec<String> = (0..100_000)
+        .map(|i| format!(r#""k{}": {}"#, i, i))
+        .collect();
+    let v = format!("{{{}}}", keys.join(", "));
+
+    let results = [
+        jtp::from_str(&v)
+            .disallow_duplicate_object_entry_name()
+            .with_max_validator_memory(64 * 1024)
+            .validate(),
+        jtp::from_reader(v.as_bytes())
+            .disallow_duplicate_object_entry_name()
+            .with_max_validator_memory(64 * 1024)
+            .validate(),
+    ];
+
+    for result in results {
+        assert!(matches!(
+            result.unwrap_err(),
+            jtp::Error::MaxValidatorMemoryExceeded { .. }
+        ));
+    }
+
+    // Without the duplicate detection, the keys are not retained.
+    assert_ok(&v, 64 * 1024);
+}
+
+#[test]
+fn long_string() {
+    let v = format!(r#"["{}"]"#, "a".repeat(1024 * 1024));
+    assert_exceeded(&v, 64 * 1024);
+    assert_ok(&v, 4 * 1024 * 1024);
+}
+
+#[test]
+fn path_of_error() {
+    let v = format!(r#"{{"a": [1, "{}"]}}"#, "a".repeat(1024 * 1024));
+    let err = jtp::from_str(&v)
+        .with_max_validator_memory(64 * 1024)
+        .validate()
+        .unwrap_err();
+    assert_eq!(err.path(), Some("/a/1"));
+}
+
+#[test]
+fn long_token_is_rejected_while_buffered() {
+    let limit = 64 * 1024;
+    for v in [
+        format!(r#"["{}"]"#, "a".repeat(1024 * 1024)),
+        format!(r#"["{}"]"#, "\\n".repeat(1024 * 1024)),
+        format!("[{}]", "1".repeat(1024 * 1024)),
+        format!("[0.{}]", "1".repeat(1024 * 1024)),
+        format!("[1e{}]", "1".repeat(1024 * 1024)),
+    ] {
+        // The policy needs the literal of the numbers, so they are buffered.
+        let results = [
+            jtp::from_str(&v)
+                .with_max_validator_memory(limit)
+                .with_number_policy(NumberPolicy::F64)
+                .validate(),
+            jtp::from_reader(v.as_bytes())
+                .with_max_validator_memory(limit)
+                .with_number_policy(NumberPolicy::F64)
+                .validate(),
+        ];
+
+        for result in results {
+            match result.unwrap_err() {
+                jtp::Error::MaxValidatorMemoryExceeded { position, .. } => {
+                    assert!(position.offset <= 2 * limit, "{}", position.offset)
+                }
+                err => panic!("unexpected error: {:?}", err),
+            }
+        }
+    }
+}
+
+#[test]
+fn long_token_is_rejected_while_fed() {
+    let limit = 64 * 1024;
+    let v = format!(r#"["{}"]"#, "a".repeat(1024 * 1024));
+    let mut validator = jtp::from_chunks().with_max_validator_memory(limit);
+
+    let mut fed = 0;
+    let err = loop {
+        let chunk = &v.as_bytes()[fed..(fed + 1024).min(v.len())];
+        fed += chunk.len();
+        if let Err(err) = validator.feed(chunk) {
+            break err;
+        }
+        assert!(fed < v.len(), "the long string is accepted");
+    };
+
+    assert!(matches!(err, jtp::Error::MaxValidatorMemoryExceeded { .. }));
+    assert!(fed <= 4 * limit, "{}", fed);
+}
+
+#[test]
+fn peeked_token_is_checked() {
+    let v = format!(r#"["{}"]"#, "a".repeat(1024 * 1024));
+    let err = jtp::from_str(&v)
+        .with_max_validator_memory(64 * 1024)
+        .validate_with_steps(1)
+        .unwrap_err();
+    assert!(matches!(err, jtp::Error::MaxValidatorMemoryExceeded { .. }));
+}
+
+#[test]
+fn reader_buffer_counts_toward_memory() {
+    let v = "[1, 2, 3]";
+    let err = jtp::Validator::new(jtp::read::IoRead::with_capacity(1024 * 1024, v.as_bytes()))
+        .with_max_validator_memory(64 * 1024)
+        .validate()
+        .unwrap_err();
+    assert!(matches!(err, jtp::Error::MaxValidatorMemoryExceeded { .. }));
+
+    jtp::Validator::new(jtp::read::IoRead::with_capacity(1024, v.as_bytes()))
+        .with_max_validator_memory(64 * 1024)
+        .validate()
+        .unwrap();
+}
@@ -0,0 +1,112 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, PrototypePollutionPolicy};
+
+fn assert_forbidden(v: &str, policy: PrototypePollutionPolicy, name: &str, path: &str) {
+    let results = [
+        jtp::from_str(v)
+            .with_prototype_pollution_policy(policy)
+            .validate(),
+        jtp::from_slice(v.as_bytes())
+            .with_prototype_pollution_policy(policy)
+            .validate(),
+        jtp::from_reader(v.as_bytes())
+            .with_prototype_pollution_policy(policy)
+            .validate(),
+    ];
+
+    for result in results {
+        let err = result.unwrap_err();
+        match &err {
+            jtp::Error::ForbiddenObjectEntryName { name: n, .. } => assert_eq!(n, name),
+            _ => panic!("unexpected error: {:?}", err),
+        }
+        assert_eq!(err.path(), Some(path));
+    }
+}
+
+fn assert_ok(v: &str, policy: PrototypePollutionPolicy) {
+    jtp::from_str(v)
+        .with_prototype_pollution_policy(policy)
+        .validate()
+        .unwrap();
+    jtp::from_slice(v.as_bytes())
+        .with_prototype_pollution_policy(policy)
+        .validate()
+        .unwrap();
+    jtp::from_reader(v.as_bytes())
+        .with_prototype_pollution_policy(policy)
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn allow_by_default() {
+    let v = r#"{"__proto__": {"isAdmin": true}, "constructor": {"prototype": 1}}"#;
+    jtp::from_str(v).validate().unwrap();
+    assert_ok(v, PrototypePollutionPolicy::Allow);
+}
+
+#[test]
+fn reject_at_any_depth() {
+    for policy in [
+        PrototypePollutionPolicy::RejectUnescaped,
+        PrototypePollutionPolicy::RejectAll,
+    ] {
+        assert_forbidden(r#"{"__proto__": {}}"#, policy, "__proto__", "/__proto__");
+        assert_forbidden(
+            r#"{"a": [{"constructor": {"prototype": 1}}]}"#,
+            policy,
+            "constructor",
+            "/a/0/constructor",
+        );
+        assert_forbidden(
+            r#"[1, {"b": {"prototype": null}}]"#,
+            policy,
+            "prototype",
+            "/1/b/prototype",
+        );
+    }
+}
+
+#[test]
+fn values_are_not_names() {
+    for policy in [
+        PrototypePollutionPolicy::RejectUnescaped,
+        PrototypePollutionPolicy::RejectAll,
+    ] {
+        assert_ok(r#"["__proto__", {"a": "constructor"}]"#, policy);
+        assert_ok(r#"{"__proto": 1, "proto__": 2, "Constructor": 3}"#, policy);
+    }
+}
+
+#[test]
+fn escaped_names() {
+    let v = r#"{"a": {"\u005f_proto__": {"isAdmin": true}}}"#;
+    assert_ok(v, PrototypePollutionPolicy::RejectUnescaped);
+    assert_forbidden(
+        v,
+        PrototypePollutionPolicy::RejectAll,
+        "__proto__",
+        "/a/__proto__",
+    );
+
+    let v = r#"{"constr\u0075ctor": 1}"#;
+    assert_ok(v, PrototypePollutionPolicy::RejectUnescaped);
+    assert_forbidden(
+        v,
+        PrototypePollutionPolicy::RejectAll,
+        "constructor",
+        "/constructor",
+    );
+}
+
+#[test]
+fn collect_all_errors() {
+    let errors = jtp::from_str(r#"{"__proto__": 1, "a": {"prototype": 2}}"#)
+        .with_prototype_pollution_policy(PrototypePollutionPolicy::RejectAll)
+        .validate_all()
+        .unwrap_err();
+    let paths: Vec<_> = errors.iter().map(|err| err.path().unwrap()).collect();
+    assert_eq!(paths, ["/__proto__", "/a/prototype"]);
+}
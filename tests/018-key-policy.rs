@@ -0,0 +1,225 @@
+mod utils;
+
+#[cfg(feature = "regex")]
+use json_threat_protection::KeyRegex;
+use json_threat_protection::{self as jtp, KeyPattern, KeyPolicy};
+
+fn validate(v: &str, policies: &[KeyPolicy]) -> Vec<Result<(), jtp::Error>> {
+    let mut str_validator = jtp::from_str(v);
+    let mut slice_validator = jtp::from_slice(v.as_bytes());
+    let mut reader_validator = jtp::from_reader(v.as_bytes());
+    for policy in policies {
+        str_validator = str_validator.with_key_policy(policy.clone());
+        slice_validator = slice_validator.with_key_policy(policy.clone());
+        reader_validator = reader_validator.with_key_policy(policy.clone());
+    }
+
+    vec![
+        str_validator.validate(),
+        slice_validator.validate(),
+        reader_validator.validate(),
+    ]
+}
+
+fn assert_forbidden(v: &str, policies: &[KeyPolicy], name: &str, path: &str) {
+    for result in validate(v, policies) {
+        let err = result.unwrap_err();
+        match &err {
+            jtp::Error::ForbiddenObjectEntryName { name: n, .. } => assert_eq!(n, name),
+            _ => panic!("unexpected error: {:?}", err),
+        }
+        assert_eq!(err.path(), Some(path));
+    }
+}
+
+fn assert_ok(v: &str, policies: &[KeyPolicy]) {
+    for result in validate(v, policies) {
+        result.unwrap();
+    }
+}
+
+fn exact(name: &str) -> KeyPattern {
+    KeyPattern::Exact(name.to_string())
+}
+
+fn prefix(prefix: &str) -> KeyPattern {
+    KeyPattern::Prefix(prefix.to_string())
+}
+
+fn glob(glob: &str) -> KeyPattern {
+    KeyPattern::Glob(glob.to_string())
+}
+
+#[test]
+fn deny_exact() {
+    let policies = [KeyPolicy::deny(vec![exact("$where"), exact("$ne")])];
+    assert_forbidden(r#"{"$where": "1"}"#, &policies, "$where", "/$where");
+    assert_forbidden(r#"[{"a": {"$ne": 1}}]"#, &policies, "$ne", "/0/a/$ne");
+    assert_ok(r#"{"$gt": 1, "where": ["$ne"]}"#, &policies);
+}
+
+#[test]
+fn deny_prefix() {
+    let policies = [KeyPolicy::deny(vec![prefix("$"), prefix("_internal")])];
+    assert_forbidden(r#"{"a": {"$gt": 1}}"#, &policies, "$gt", "/a/$gt");
+    assert_forbidden(
+        r#"{"_internal_id": 1}"#,
+        &policies,
+        "_internal_id",
+        "/_internal_id",
+    );
+    assert_ok(r#"{"a$": 1, "internal": 2}"#, &policies);
+}
+
+#[test]
+fn deny_glob() {
+    let policies = [KeyPolicy::deny(vec![glob("x-*-secret"), glob("tmp?")])];
+    assert_forbidden(
+        r#"{"x-api-secret": 1}"#,
+        &policies,
+        "x-api-secret",
+        "/x-api-secret",
+    );
+    assert_forbidden(r#"{"x--secret": 1}"#, &policies, "x--secret", "/x--secret");
+    assert_forbidden(r#"{"tmp1": 1}"#, &policies, "tmp1", "/tmp1");
+    assert_forbidden(r#"{"tmpé": 1}"#, &policies, "tmpé", "/tmpé");
+    assert_ok(
+        r#"{"x-secret": 1, "x-api-secrets": 2, "tmp": 3, "tmp12": 4}"#,
+        &policies,
+    );
+}
+
+#[test]
+fn glob_backtracking() {
+    let policies = [KeyPolicy::deny(vec![glob("*a*a*a*a*a*b")])];
+    let name = "a".repeat(10_000);
+    assert_ok(&format!(r#"{{"{}": 1}}"#, name), &policies);
+
+    let policies = [KeyPolicy::allow(vec![glob("*"), glob("")])];
+    assert_ok(r#"{"": 1, "anything": 2}"#, &policies);
+}
+
+#[test]
+fn glob_multibyte() {
+    // The `*` backtracks over whole characters of the name.
+    let policies = [KeyPolicy::deny(vec![glob("é*ü?"), glob("*日本")])];
+    assert_forbidden(r#"{"éaüüb": 1}"#, &policies, "éaüüb", "/éaüüb");
+    assert_forbidden(r#"{"éüü": 1}"#, &policies, "éüü", "/éüü");
+    assert_forbidden(r#"{"日日本": 1}"#, &policies, "日日本", "/日日本");
+    assert_ok(r#"{"éü": 1, "éaübc": 2, "日本語": 3}"#, &policies);
+}
+
+#[test]
+fn allow_list() {
+    let policies = [KeyPolicy::allow(vec![exact("name"), prefix("filter_")])];
+    assert_ok(
+        r#"{"name": "a", "filter_age": {"filter_gt": 18}}"#,
+        &policies,
+    );
+    assert_forbidden(r#"{"name": "a", "age": 1}"#, &policies, "age", "/age");
+}
+
+#[test]
+fn at_depth() {
+    let policies = [
+        KeyPolicy::allow(vec![exact("name"), exact("query")]).at_depth(1),
+        KeyPolicy::deny(vec![prefix("$")]).at_depth(2),
+    ];
+    assert_ok(
+        r#"{"name": "a", "query": {"age": 1, "x": {"$gt": 1}}}"#,
+        &policies,
+    );
+    assert_forbidden(
+        r#"{"query": {"$where": "1"}}"#,
+        &policies,
+        "$where",
+        "/query/$where",
+    );
+    assert_forbidden(r#"{"query": {}, "age": 1}"#, &policies, "age", "/age");
+
+    // Objects nested in arrays are one level deeper.
+    assert_ok(r#"[{"age": 1}]"#, &policies);
+}
+
+#[test]
+fn escaped_names() {
+    let policies = [KeyPolicy::deny(vec![exact("$where")])];
+    assert_forbidden(r#"{"\u0024where": "1"}"#, &policies, "$where", "/$where");
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn deny_regex() {
+    let policies = [KeyPolicy::deny(vec![KeyPattern::Regex(
+        KeyRegex::new(r"^\$(where|ne|gt|lt)$").unwrap(),
+    )])];
+    assert_forbidden(r#"{"a": {"$lt": 1}}"#, &policies, "$lt", "/a/$lt");
+    assert_ok(r#"{"$eq": 1, "a$ne": 2}"#, &policies);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_config() {
+    let config: jtp::ValidatorConfig = serde_json::from_str(
+        r#"{"key_policies": [{"mode": "Deny", "patterns": [{"Prefix": "$"}]}]}"#,
+    )
+    .unwrap();
+    assert_eq!(config.key_policies, [KeyPolicy::deny(vec![prefix("$")])]);
+
+    let err = jtp::from_str(r#"{"$ne": 1}"#)
+        .with_config(config)
+        .validate()
+        .unwrap_err();
+    assert!(matches!(err, jtp::Error::ForbiddenObjectEntryName { .. }));
+}
+
+#[cfg(all(feature = "serde", feature = "regex"))]
+#[test]
+fn serde_regex() {
+    let config: jtp::ValidatorConfig = serde_json::from_str(
+        r#"{"key_policies": [{"mode": "Deny", "patterns": [{"Regex": "^\\$ne$"}]}]}"#,
+    )
+    .unwrap();
+    let policy = KeyPolicy::deny(vec![KeyPattern::Regex(KeyRegex::new(r"^\$ne$").unwrap())]);
+    assert_eq!(config.key_policies, [policy]);
+
+    let err = jtp::from_str(r#"{"$ne": 1}"#)
+        .with_config(config)
+        .validate()
+        .unwrap_err();
+    assert!(matches!(err, jtp::Error::ForbiddenObjectEntryName { .. }));
+
+    assert!(serde_json::from_str::<jtp::ValidatorConfig>(
+        r#"{"key_policies": [{"mode": "Deny", "patterns": [{"Regex": "("}]}]}"#,
+    )
+    .is_err());
+}
+
+#[cfg(all(feature = "serde", not(feature = "regex")))]
+#[test]
+fn regex_requires_feature() {
+    let config: jtp::ValidatorConfig = serde_json::from_str(
+        r#"{"key_policies": [{"mode": "Deny", "patterns": [{"Regex": "^\\$ne$"}]}]}"#,
+    )
+    .unwrap();
+    match &config.key_policies[0].patterns[0] {
+        KeyPattern::Regex(regex) => assert_eq!(regex.as_str(), r"^\$ne$"),
+        pattern => panic!("unexpected pattern: {:?}", pattern),
+    }
+
+    let err = jtp::from_str(r#"{"a": 1}"#)
+        .with_config(config)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::FeatureNotEnabled {
+                option: "key_policies",
+                feature: "regex"
+            }
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+}
@@ -0,0 +1,92 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, KeyCharset};
+
+fn assert_forbidden(v: &str, charset: KeyCharset, character: char, path: &str) {
+    let results = [
+        jtp::from_str(v).with_key_charset(charset).validate(),
+        jtp::from_slice(v.as_bytes())
+            .with_key_charset(charset)
+            .validate(),
+        jtp::from_reader(v.as_bytes())
+            .with_key_charset(charset)
+            .validate(),
+    ];
+
+    for result in results {
+        let err = result.unwrap_err();
+        match &err {
+            jtp::Error::ForbiddenCharacterInObjectEntryName { character: c, .. } => {
+                assert_eq!(*c, character)
+            }
+            _ => panic!("unexpected error: {:?}", err),
+        }
+        assert_eq!(err.path(), Some(path));
+    }
+}
+
+fn assert_ok(v: &str, charset: KeyCharset) {
+    jtp::from_str(v)
+        .with_key_charset(charset)
+        .validate()
+        .unwrap();
+    jtp::from_slice(v.as_bytes())
+        .with_key_charset(charset)
+        .validate()
+        .unwrap();
+    jtp::from_reader(v.as_bytes())
+        .with_key_charset(charset)
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn allow_any_by_default() {
+    assert_ok(
+        r#"{"a\u0000b": 1, "a\nb": 2, "a b": 3, "a.b": 4, "中文": 5}"#,
+        KeyCharset::default(),
+    );
+}
+
+#[test]
+fn control_characters() {
+    let charset = KeyCharset {
+        allow_control_characters: false,
+        ..Default::default()
+    };
+    assert_forbidden(r#"{"a\u0000b": 1}"#, charset, '\0', "/a\u{0}b");
+    assert_forbidden(r#"{"a": {"b\nc": 1}}"#, charset, '\n', "/a/b\nc");
+    assert_forbidden(r#"{"\u007f": 1}"#, charset, '\u{7f}', "/\u{7f}");
+    assert_ok(r#"{"a b": 1, "a.b": 2, "中文": 3}"#, charset);
+}
+
+#[test]
+fn whitespace() {
+    let charset = KeyCharset {
+        allow_whitespace: false,
+        ..Default::default()
+    };
+    assert_forbidden(r#"{"a b": 1}"#, charset, ' ', "/a b");
+    assert_forbidden(r#"[{"a　b": 1}]"#, charset, '\u{3000}', "/0/a\u{3000}b");
+    assert_ok(r#"{"a.b": 1, "a\u0000b": 2}"#, charset);
+}
+
+#[test]
+fn dots() {
+    let charset = KeyCharset {
+        allow_dots: false,
+        ..Default::default()
+    };
+    assert_forbidden(r#"{"a.b": 1}"#, charset, '.', "/a.b");
+    assert_forbidden(r#"{"a\u002eb": 1}"#, charset, '.', "/a.b");
+    assert_ok(r#"{"a b": 1, "a": "b.c"}"#, charset);
+}
+
+#[test]
+fn ascii_identifier() {
+    let charset = KeyCharset::ascii_identifier();
+    assert_ok(r#"{"user_id": 1, "Name2": {"_": "a.b c"}}"#, charset);
+    assert_forbidden(r#"{"user-id": 1}"#, charset, '-', "/user-id");
+    assert_forbidden(r#"{"a": {"$ne": 1}}"#, charset, '$', "/a/$ne");
+    assert_forbidden(r#"{"café": 1}"#, charset, 'é', "/café");
+}
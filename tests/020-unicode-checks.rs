@@ -0,0 +1,132 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, UnicodeChecks};
+
+fn assert_rejected(v: &str, checks: UnicodeChecks, code_point: u32, path: &str) {
+    let results = [
+        jtp::from_str(v).with_unicode_checks(checks).validate(),
+        jtp::from_slice(v.as_bytes())
+            .with_unicode_checks(checks)
+            .validate(),
+        jtp::from_reader(v.as_bytes())
+            .with_unicode_checks(checks)
+            .validate(),
+    ];
+
+    for result in results {
+        let err = result.unwrap_err();
+        match &err {
+            jtp::Error::ForbiddenCodePointInString { code_point: c, .. } => {
+                assert_eq!(*c, code_point)
+            }
+            _ => panic!("unexpected error: {:?}", err),
+        }
+        assert_eq!(err.path(), Some(path));
+    }
+}
+
+fn assert_ok(v: &str, checks: UnicodeChecks) {
+    jtp::from_str(v)
+        .with_unicode_checks(checks)
+        .validate()
+        .unwrap();
+    jtp::from_slice(v.as_bytes())
+        .with_unicode_checks(checks)
+        .validate()
+        .unwrap();
+    jtp::from_reader(v.as_bytes())
+        .with_unicode_checks(checks)
+        .validate()
+        .unwrap();
+}
+
+const ALL: &str = r#"[
+    "\u202a", "\u2069", "\u200b", "\ufeff", "\ufdd0",
+    "\uffff", "\ud83f\udffe", "\ue000", "\udbff\udffd", "\u0000"
+]"#;
+
+#[test]
+fn accept_all_by_default() {
+    assert_ok(ALL, UnicodeChecks::default());
+}
+
+#[test]
+fn bidi_controls() {
+    let checks = UnicodeChecks {
+        reject_bidi_controls: true,
+        ..Default::default()
+    };
+    assert_rejected(r#"["a\u202eb"]"#, checks, 0x202E, "/0");
+    assert_rejected("{\"a\": [1, \"\u{2066}\"]}", checks, 0x2066, "/a/1");
+    assert_ok(r#"["\u2029", "\u2065", "\u200b"]"#, checks);
+}
+
+#[test]
+fn zero_width() {
+    let checks = UnicodeChecks {
+        reject_zero_width: true,
+        ..Default::default()
+    };
+    assert_rejected(r#"{"a": "b\u200dc"}"#, checks, 0x200D, "/a");
+    assert_rejected(r#"["\u2060"]"#, checks, 0x2060, "/0");
+    assert_rejected("[\"\u{feff}\"]", checks, 0xFEFF, "/0");
+    assert_ok(r#"["\u00a0", "\u200e", "\u202e"]"#, checks);
+}
+
+#[test]
+fn noncharacters() {
+    let checks = UnicodeChecks {
+        reject_noncharacters: true,
+        ..Default::default()
+    };
+    assert_rejected(r#"["\ufdef"]"#, checks, 0xFDEF, "/0");
+    assert_rejected(r#"["\ufffe"]"#, checks, 0xFFFE, "/0");
+    assert_rejected(r#"["\ud83f\udfff"]"#, checks, 0x1FFFF, "/0");
+    assert_ok(r#"["\ufdcf", "\ufdf0", "\ufffd", "\ud83d\ude00"]"#, checks);
+}
+
+#[test]
+fn private_use() {
+    let checks = UnicodeChecks {
+        reject_private_use: true,
+        ..Default::default()
+    };
+    assert_rejected(r#"["\uf8ff"]"#, checks, 0xF8FF, "/0");
+    assert_rejected(r#"["\udb80\udc00"]"#, checks, 0xF0000, "/0");
+    assert_rejected("[\"\u{10FFFD}\"]", checks, 0x10FFFD, "/0");
+    assert_ok(r#"["\uf900", "\udbff\udfff"]"#, checks);
+}
+
+#[test]
+fn nul() {
+    let checks = UnicodeChecks {
+        reject_nul: true,
+        ..Default::default()
+    };
+    assert_rejected(r#"["abc\u0000"]"#, checks, 0, "/0");
+    assert_ok(r#"["\u0001", "\u202e"]"#, checks);
+}
+
+#[test]
+fn object_entry_names_are_not_checked() {
+    assert_ok(r#"{"\u202e": "a"}"#, UnicodeChecks::all());
+}
+
+#[test]
+fn collect_all_errors() {
+    let errors = jtp::from_str(ALL)
+        .with_unicode_checks(UnicodeChecks::all())
+        .validate_all()
+        .unwrap_err();
+    let code_points: Vec<u32> = errors
+        .iter()
+        .map(|err| match err {
+            jtp::Error::ForbiddenCodePointInString { code_point, .. } => *code_point,
+            _ => panic!("unexpected error: {:?}", err),
+        })
+        .collect();
+    assert_eq!(
+        code_points,
+        [0x202A, 0x2069, 0x200B, 0xFEFF, 0xFDD0, 0xFFFF, 0x1FFFE, 0xE000, 0x10FFFD, 0]
+    );
+}
@@ -0,0 +1,152 @@
+#![cfg(feature = "unicode-normalization")]
+
+mod utils;
+
+use json_threat_protection::{self as jtp, KeyNormalization, NormalizationForm};
+
+fn assert_duplicate(v: &str, normalization: KeyNormalization, key: &str, earlier_key: &str) {
+    // The spellings are kept, so the readers which could not look back report them as well.
+    let mut chunks = jtp::from_chunks()
+        .disallow_duplicate_object_entry_name()
+        .with_key_normalization(normalization);
+    let fed = v
+        .as_bytes()
+        .chunks(1)
+        .try_for_each(|chunk| chunks.feed(chunk).map(|_| ()))
+        .and_then(|_| chunks.finish());
+
+    let results = [
+        jtp::from_str(v)
+            .disallow_duplicate_object_entry_name()
+            .with_key_normalization(normalization)
+            .validate(),
+        jtp::from_slice(v.as_bytes())
+            .disallow_duplicate_object_entry_name()
+            .with_key_normalization(normalization)
+            .validate(),
+        jtp::from_reader(v.as_bytes())
+            .disallow_duplicate_object_entry_name()
+            .with_key_normalization(normalization)
+            .validate(),
+        fed,
+    ];
+
+    for result in results {
+        match result.unwrap_err() {
+            jtp::Error::DuplicateObjectEntryName {
+                key: k,
+                earlier_key: e,
+                ..
+            } => {
+                assert_eq!(k, key);
+                assert_eq!(e.as_deref(), Some(earlier_key));
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+fn assert_ok(v: &str, normalization: KeyNormalization) {
+    jtp::from_str(v)
+        .disallow_duplicate_object_entry_name()
+        .with_key_normalization(normalization)
+        .validate()
+        .unwrap();
+    jtp::from_reader(v.as_bytes())
+        .disallow_duplicate_object_entry_name()
+        .with_key_normalization(normalization)
+        .validate()
+        .unwrap();
+}
+
+const NFC: KeyNormalization = KeyNormalization {
+    form: Some(NormalizationForm::Nfc),
+    case_fold: false,
+};
+
+const NFKC: KeyNormalization = KeyNormalization {
+    form: Some(NormalizationForm::Nfkc),
+    case_fold: false,
+};
+
+const CASE_FOLD: KeyNormalization = KeyNormalization {
+    form: None,
+    case_fold: true,
+};
+
+#[test]
+fn keep_names_by_default() {
+    assert_ok(
+        r#"{"é": 1, "é": 2, "A": 3, "a": 4, "Ａ": 5}"#,
+        KeyNormalization::default(),
+    );
+}
+
+#[test]
+fn nfc() {
+    assert_duplicate(r#"{"é": 1, "é": 2}"#, NFC, "e\u{301}", "\u{e9}");
+    assert_duplicate(r#"{"é": 1, "é": 2}"#, NFC, "\u{e9}", "e\u{301}");
+    assert_duplicate(r#"{"a": {"Å": 1, "Å": 2}}"#, NFC, "\u{c5}", "\u{212b}");
+    assert_ok(r#"{"Ａ": 1, "A": 2, "a": 3}"#, NFC);
+}
+
+#[test]
+fn nfkc() {
+    assert_duplicate(r#"{"A": 1, "Ａ": 2}"#, NFKC, "\u{ff21}", "A");
+    assert_duplicate(r#"{"ﬁle": 1, "file": 2}"#, NFKC, "file", "\u{fb01}le");
+    assert_duplicate(r#"{"é": 1, "é": 2}"#, NFKC, "e\u{301}", "\u{e9}");
+    assert_ok(r#"{"A": 1, "a": 2}"#, NFKC);
+}
+
+#[test]
+fn case_fold() {
+    assert_duplicate(r#"{"Name": 1, "nAME": 2}"#, CASE_FOLD, "nAME", "Name");
+    assert_duplicate(r#"{"É": 1, "é": 2}"#, CASE_FOLD, "\u{e9}", "\u{c9}");
+    assert_ok(r#"{"é": 1, "é": 2}"#, CASE_FOLD);
+
+    // Full case folding rather than lowercasing.
+    assert_duplicate(
+        r#"{"Stra\u00dfe": 1, "STRASSE": 2}"#,
+        CASE_FOLD,
+        "STRASSE",
+        "Stra\u{df}e",
+    );
+    assert_duplicate(
+        r#"{"\u03c3\u03bf\u03c6\u03bf\u03c2": 1, "\u03a3\u039f\u03a6\u039f\u03a3": 2}"#,
+        CASE_FOLD,
+        "\u{3a3}\u{39f}\u{3a6}\u{39f}\u{3a3}",
+        "\u{3c3}\u{3bf}\u{3c6}\u{3bf}\u{3c2}",
+    );
+
+    // The confusable characters are out of scope.
+    assert_ok(r#"{"\u0430dmin": 1, "admin": 2}"#, CASE_FOLD);
+
+    let normalization = KeyNormalization {
+        form: Some(NormalizationForm::Nfkc),
+        case_fold: true,
+    };
+    assert_duplicate(
+        r#"{"ａdmin": 1, "ADMIN": 2}"#,
+        normalization,
+        "ADMIN",
+        "\u{ff41}dmin",
+    );
+}
+
+#[test]
+fn other_constraints_see_original_names() {
+    let err = jtp::from_str(r#"{"Ａ": 1}"#)
+        .with_key_normalization(NFKC)
+        .with_max_object_entry_name_length(1)
+        .validate()
+        .unwrap_err();
+    assert_eq!(err.path(), Some("/\u{ff21}"));
+}
+
+#[test]
+fn only_with_duplicates_disallowed() {
+    jtp::from_str(r#"{"A": 1, "Ａ": 2}"#)
+        .with_key_normalization(NFKC)
+        .validate()
+        .unwrap();
+}
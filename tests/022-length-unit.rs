@@ -0,0 +1,132 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, LengthUnit};
+
+fn validate(v: &str, unit: LengthUnit, limit: usize) -> Vec<Result<(), jtp::Error>> {
+    vec![
+        jtp::from_str(v)
+            .with_length_unit(unit)
+            .with_max_string_length(limit)
+            .with_max_object_entry_name_length(limit)
+            .validate(),
+        jtp::from_slice(v.as_bytes())
+            .with_length_unit(unit)
+            .with_max_string_length(limit)
+            .with_max_object_entry_name_length(limit)
+            .validate(),
+        jtp::from_reader(v.as_bytes())
+            .with_length_unit(unit)
+            .with_max_string_length(limit)
+            .with_max_object_entry_name_length(limit)
+            .validate(),
+    ]
+}
+
+fn assert_string_exceeded(v: &str, unit: LengthUnit, limit: usize) {
+    for result in validate(v, unit, limit) {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, jtp::Error::MaxStringLengthExceeded { unit: u, .. } if u == unit),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
+
+fn assert_name_exceeded(v: &str, unit: LengthUnit, limit: usize) {
+    for result in validate(v, unit, limit) {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, jtp::Error::MaxObjectEntryNameLengthExceeded { unit: u, .. } if u == unit),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
+
+fn assert_ok(v: &str, unit: LengthUnit, limit: usize) {
+    for result in validate(v, unit, limit) {
+        result.unwrap();
+    }
+}
+
+// 4 chars, 5 UTF-16 code units and 8 bytes.
+const STRING: &str = r#"["aé😀b"]"#;
+const NAME: &str = r#"{"aé😀b": 1}"#;
+
+#[test]
+fn bytes_by_default() {
+    for result in [
+        jtp::from_str(STRING).with_max_string_length(7).validate(),
+        jtp::from_str(NAME)
+            .with_max_object_entry_name_length(7)
+            .validate(),
+    ] {
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("limit: 7 bytes"), "{}", err);
+    }
+
+    assert_string_exceeded(STRING, LengthUnit::Bytes, 7);
+    assert_name_exceeded(NAME, LengthUnit::Bytes, 7);
+    assert_ok(STRING, LengthUnit::Bytes, 8);
+    assert_ok(NAME, LengthUnit::Bytes, 8);
+}
+
+#[test]
+fn chars() {
+    assert_string_exceeded(STRING, LengthUnit::Chars, 3);
+    assert_name_exceeded(NAME, LengthUnit::Chars, 3);
+    assert_ok(STRING, LengthUnit::Chars, 4);
+    assert_ok(NAME, LengthUnit::Chars, 4);
+}
+
+#[test]
+fn utf16_code_units() {
+    assert_string_exceeded(STRING, LengthUnit::Utf16CodeUnits, 4);
+    assert_name_exceeded(NAME, LengthUnit::Utf16CodeUnits, 4);
+    assert_ok(STRING, LengthUnit::Utf16CodeUnits, 5);
+    assert_ok(NAME, LengthUnit::Utf16CodeUnits, 5);
+}
+
+#[cfg(feature = "unicode-segmentation")]
+#[test]
+fn graphemes() {
+    // 2 graphemes, 4 chars.
+    let v = r#"["é👍🏽"]"#;
+    assert_string_exceeded(v, LengthUnit::Graphemes, 1);
+    assert_ok(v, LengthUnit::Graphemes, 2);
+    assert_string_exceeded(v, LengthUnit::Chars, 3);
+}
+
+#[cfg(not(feature = "unicode-segmentation"))]
+#[test]
+fn graphemes_require_feature() {
+    for result in validate(r#"["a"]"#, LengthUnit::Graphemes, 2) {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                jtp::Error::FeatureNotEnabled {
+                    option: "length_unit",
+                    feature: "unicode-segmentation"
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
+
+#[test]
+fn error_message() {
+    let err = jtp::from_str(STRING)
+        .with_length_unit(LengthUnit::Utf16CodeUnits)
+        .with_max_string_length(4)
+        .validate()
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("limit: 4 UTF-16 code units"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,128 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, read::FeedRead};
+
+fn validate(v: &str, raw_length: usize, escapes: usize) -> Vec<Result<(), jtp::Error>> {
+    vec![
+        jtp::from_str(v)
+            .with_max_string_raw_length(raw_length)
+            .with_max_string_escapes(escapes)
+            .validate(),
+        jtp::from_slice(v.as_bytes())
+            .with_max_string_raw_length(raw_length)
+            .with_max_string_escapes(escapes)
+            .validate(),
+        jtp::from_reader(v.as_bytes())
+            .with_max_string_raw_length(raw_length)
+            .with_max_string_escapes(escapes)
+            .validate(),
+    ]
+}
+
+fn assert_raw_length_exceeded(v: &str, limit: usize, path: &str) {
+    for result in validate(v, limit, jtp::NO_LIMIT) {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, jtp::Error::MaxStringRawLengthExceeded { limit: l, .. } if l == limit),
+            "unexpected error: {:?}",
+            err
+        );
+        assert_eq!(err.path(), Some(path));
+    }
+}
+
+fn assert_escapes_exceeded(v: &str, limit: usize, path: &str) {
+    for result in validate(v, jtp::NO_LIMIT, limit) {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, jtp::Error::MaxStringEscapesExceeded { limit: l, .. } if l == limit),
+            "unexpected error: {:?}",
+            err
+        );
+        assert_eq!(err.path(), Some(path));
+    }
+}
+
+fn assert_ok(v: &str, raw_length: usize, escapes: usize) {
+    for result in validate(v, raw_length, escapes) {
+        result.unwrap();
+    }
+}
+
+#[test]
+fn raw_length() {
+    // 12 bytes in the source, 2 bytes decoded.
+    let v = r#"["\u0041\u0041"]"#;
+    assert_raw_length_exceeded(v, 11, "/0");
+    assert_ok(v, 12, jtp::NO_LIMIT);
+
+    // The decoded length is limited separately.
+    let err = jtp::from_str(v)
+        .with_max_string_length(1)
+        .with_max_string_raw_length(12)
+        .validate()
+        .unwrap_err();
+    assert!(matches!(err, jtp::Error::MaxStringLengthExceeded { .. }));
+    jtp::from_str(v)
+        .with_max_string_length(2)
+        .with_max_string_raw_length(12)
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn raw_length_of_names() {
+    // The name is rejected before it is read entirely, so the path points at the object.
+    let v = r#"{"a": {"\n\t": 1}}"#;
+    assert_raw_length_exceeded(v, 3, "/a");
+    assert_ok(v, 4, jtp::NO_LIMIT);
+}
+
+#[test]
+fn escapes() {
+    let v = r#"["a\"b\\c\/d\be\ff\ng\rh\ti\u0041"]"#;
+    assert_escapes_exceeded(v, 8, "/0");
+    assert_ok(v, jtp::NO_LIMIT, 9);
+    assert_ok(r#"["plain", "a/b"]"#, jtp::NO_LIMIT, 0);
+}
+
+#[test]
+fn surrogate_pairs_count_twice() {
+    let v = r#"{"k": "\ud83d\ude00"}"#;
+    assert_escapes_exceeded(v, 1, "/k");
+    assert_ok(v, jtp::NO_LIMIT, 2);
+}
+
+#[test]
+fn escapes_of_names() {
+    let v = r#"[{"\u0061\u0062": 1}]"#;
+    assert_escapes_exceeded(v, 1, "/0");
+    assert_ok(v, jtp::NO_LIMIT, 2);
+}
+
+#[test]
+fn escapes_per_string() {
+    let v = r#"["\n", "\n", "\n"]"#;
+    assert_ok(v, jtp::NO_LIMIT, 1);
+}
+
+#[test]
+fn rejected_before_end_of_string() {
+    let mut validator = jtp::Validator::new(FeedRead::new()).with_max_string_raw_length(8);
+    let err = validator.feed(br#"[1, "0123456789"#).unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxStringRawLengthExceeded { limit: 8, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+    assert_eq!(err.path(), Some("/1"));
+
+    let mut validator = jtp::Validator::new(FeedRead::new()).with_max_string_escapes(2);
+    let err = validator.feed(br#"{"a": "\n\n\n"#).unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxStringEscapesExceeded { limit: 2, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+    assert_eq!(err.path(), Some("/a"));
+}
@@ -0,0 +1,264 @@
+mod utils;
+
+use json_threat_protection::{
+    self as jtp,
+    event::{Event, EventReader},
+    read::{LoneSurrogate, Position, Read, SurrogateKind},
+    rule::{Context, Rule, RuleError},
+    LexerError, ReadError, SurrogatePolicy,
+};
+use std::sync::{Arc, Mutex};
+
+fn validate(v: &str, policy: SurrogatePolicy) -> Vec<Result<(), jtp::Error>> {
+    vec![
+        jtp::from_str(v).with_surrogate_policy(policy).validate(),
+        jtp::from_slice(v.as_bytes())
+            .with_surrogate_policy(policy)
+            .validate(),
+        jtp::from_reader(v.as_bytes())
+            .with_surrogate_policy(policy)
+            .validate(),
+    ]
+}
+
+fn strings<R: Read>(mut events: EventReader<R>) -> Vec<String> {
+    let mut strings = Vec::new();
+    while let Some((event, _)) = events.next_event().unwrap() {
+        match event {
+            Event::Key(s) | Event::String(s) => strings.push(s.to_string()),
+            _ => (),
+        }
+    }
+    strings
+}
+
+fn assert_lone_high(v: &str, offset: usize) {
+    for result in validate(v, SurrogatePolicy::Reject) {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                jtp::Error::LexerError(LexerError::ReadError(ReadError::LoneHighSurrogate(
+                    Position { line: 1, column, offset: o }
+                ))) if column == offset && o == offset
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
+
+fn assert_lone_low(v: &str, offset: usize) {
+    for result in validate(v, SurrogatePolicy::Reject) {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                jtp::Error::LexerError(LexerError::ReadError(ReadError::LoneLowSurrogate(
+                    Position { line: 1, column, offset: o }
+                ))) if column == offset && o == offset
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
+
+fn assert_replaced(v: &str, expected: &[&str]) {
+    for result in validate(v, SurrogatePolicy::Replace) {
+        result.unwrap();
+    }
+
+    let events = jtp::from_str(v)
+        .with_surrogate_policy(SurrogatePolicy::Replace)
+        .into_events();
+    assert_eq!(strings(events), expected);
+    let events = jtp::from_reader(v.as_bytes())
+        .with_surrogate_policy(SurrogatePolicy::Replace)
+        .into_events();
+    assert_eq!(strings(events), expected);
+}
+
+#[test]
+fn reject_by_default() {
+    let err = jtp::from_str(r#"["\uD800"]"#).validate().unwrap_err();
+    assert!(matches!(
+        err,
+        jtp::Error::LexerError(LexerError::ReadError(ReadError::LoneHighSurrogate(_)))
+    ));
+}
+
+#[test]
+fn reject_lone_high_surrogates() {
+    // the position is right after the lone surrogate
+    assert_lone_high(r#"["\uD800"]"#, 8);
+    assert_lone_high(r#"["ab\uDBFFc"]"#, 10);
+    assert_lone_high(r#"["\uD800\n"]"#, 8);
+    assert_lone_high(r#"["\uD800\u0041"]"#, 8);
+    assert_lone_high(r#"{"\uD800": 1}"#, 8);
+}
+
+#[test]
+fn reject_lone_low_surrogates() {
+    assert_lone_low(r#"["\uDC00"]"#, 8);
+    assert_lone_low(r#"["a\uDFFF\uD800"]"#, 9);
+    assert_lone_low(r#"["\ud83d\ude00\ude00"]"#, 20);
+}
+
+#[test]
+fn invalid_escape_sequence_after_high_surrogate() {
+    for policy in [SurrogatePolicy::Reject, SurrogatePolicy::Replace] {
+        for result in validate(r#"["\uD800\x"]"#, policy) {
+            let err = result.unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    jtp::Error::LexerError(LexerError::ReadError(
+                        ReadError::InvalidEscapeSequence(_)
+                    ))
+                ),
+                "unexpected error: {:?}",
+                err
+            );
+        }
+    }
+}
+
+#[test]
+fn replace() {
+    assert_replaced(
+        r#"{"\uD800": ["\uDC00", "a\uD800b", "\uD800\n", "\uD800\u0041"]}"#,
+        &[
+            "\u{FFFD}",
+            "\u{FFFD}",
+            "a\u{FFFD}b",
+            "\u{FFFD}\n",
+            "\u{FFFD}A",
+        ],
+    );
+    assert_replaced(
+        r#"["\uD800\uD83D\uDE00", "\uDE00\uD83D\uDE00\uD83D"]"#,
+        &["\u{FFFD}\u{1F600}", "\u{FFFD}\u{1F600}\u{FFFD}"],
+    );
+}
+
+#[test]
+fn replacement_counts_toward_length() {
+    let v = r#"["\uD800"]"#;
+    let err = jtp::from_str(v)
+        .with_surrogate_policy(SurrogatePolicy::Replace)
+        .with_max_string_length(2)
+        .validate()
+        .unwrap_err();
+    assert!(matches!(err, jtp::Error::MaxStringLengthExceeded { .. }));
+}
+
+/// Collects the lone surrogates with the paths of their strings.
+struct Surrogates(Arc<Mutex<Vec<(String, LoneSurrogate)>>>);
+
+impl Rule for Surrogates {
+    fn on_lone_surrogate(
+        &mut self,
+        ctx: &Context<'_>,
+        surrogate: &LoneSurrogate,
+    ) -> Result<(), RuleError> {
+        self.0.lock().unwrap().push((ctx.path(), *surrogate));
+        Ok(())
+    }
+}
+
+fn reported<R: Read>(validator: jtp::Validator<R>) -> Vec<(String, SurrogateKind, u16, usize)> {
+    let found = Arc::new(Mutex::new(Vec::new()));
+    validator
+        .with_surrogate_policy(SurrogatePolicy::Report)
+        .with_rule(Surrogates(found.clone()))
+        .validate()
+        .unwrap();
+
+    let found = found.lock().unwrap();
+    found
+        .iter()
+        .map(|(path, s)| {
+            assert_eq!(s.position.column, s.position.offset);
+            (path.clone(), s.kind, s.code_unit, s.position.offset)
+        })
+        .collect()
+}
+
+#[test]
+fn report() {
+    let v = r#"{"a": ["ok", "\uD800"], "\uDC00": "\uD83D\uDE00"}"#;
+    for result in validate(v, SurrogatePolicy::Report) {
+        result.unwrap();
+    }
+
+    let expected = [
+        ("/a/1".to_string(), SurrogateKind::High, 0xD800, 14),
+        ("/\u{FFFD}".to_string(), SurrogateKind::Low, 0xDC00, 25),
+    ];
+    assert_eq!(reported(jtp::from_str(v)), expected);
+    assert_eq!(reported(jtp::from_reader(v.as_bytes())), expected);
+}
+
+#[test]
+fn report_escape_positions() {
+    let v = r#"["\uD800\uD800", "\uD800\n", "a\uDFFF\uD83D\uDE00\uDC00"]"#;
+    let expected = [
+        ("/0".to_string(), SurrogateKind::High, 0xD800, 2),
+        ("/0".to_string(), SurrogateKind::High, 0xD800, 8),
+        ("/1".to_string(), SurrogateKind::High, 0xD800, 18),
+        ("/2".to_string(), SurrogateKind::Low, 0xDFFF, 31),
+        ("/2".to_string(), SurrogateKind::Low, 0xDC00, 49),
+    ];
+    assert_eq!(reported(jtp::from_str(v)), expected);
+    assert_eq!(reported(jtp::from_slice(v.as_bytes())), expected);
+    assert_eq!(reported(jtp::from_reader(v.as_bytes())), expected);
+}
+
+#[test]
+fn rule_rejects_reported_surrogate() {
+    struct NoLoneSurrogates;
+
+    impl Rule for NoLoneSurrogates {
+        fn on_lone_surrogate(
+            &mut self,
+            _ctx: &Context<'_>,
+            surrogate: &LoneSurrogate,
+        ) -> Result<(), RuleError> {
+            Err(format!(
+                "lone surrogate {:X} at {}",
+                surrogate.code_unit, surrogate.position.offset
+            )
+            .into())
+        }
+    }
+
+    let err = jtp::from_str(r#"{"a": ["ok", "\uD800"]}"#)
+        .with_surrogate_policy(SurrogatePolicy::Report)
+        .with_rule(NoLoneSurrogates)
+        .validate()
+        .unwrap_err();
+    match err {
+        jtp::Error::Custom {
+            ref path,
+            ref source,
+            ..
+        } => {
+            assert_eq!(path, "/a/1");
+            assert_eq!(source.to_string(), "lone surrogate D800 at 14");
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn with_config() {
+    let config = jtp::ValidatorConfig {
+        surrogate_policy: SurrogatePolicy::Replace,
+        ..Default::default()
+    };
+    jtp::from_str(r#"["\uD800"]"#)
+        .with_config(config)
+        .validate()
+        .unwrap();
+}
@@ -0,0 +1,164 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, Progress};
+
+const PAYLOADS: &[&str] = &[
+    r#"{"a": [1, -2.5e+3, "s\n\u00e9\ud83d\ude00", true, false, null], "b": {"c": {}}}"#,
+    "\n [ 0 , 10 , 1e5 , \"x\" ] \n",
+    "123",
+    "-0.5E-10",
+    r#""\u0041\""#,
+    "true",
+    "{}",
+    r#"{"a": 1, "a": 2}"#,
+    r#"{"a": 1"#,
+    r#"[1, 2,]"#,
+    r#"[01]"#,
+    r#"["\x"]"#,
+    r#"["\uD800"]"#,
+    r#"[tru]"#,
+    r#"[1] 2"#,
+    r#"[1] x"#,
+    r#"1."#,
+    "",
+    "   ",
+];
+
+fn expected(v: &str) -> String {
+    format!(
+        "{:?}",
+        jtp::from_reader(v.as_bytes()).with_max_depth(2).validate()
+    )
+}
+
+fn feed(chunks: &[&[u8]]) -> Result<(), jtp::Error> {
+    let mut validator = jtp::from_chunks().with_max_depth(2);
+    for chunk in chunks {
+        validator.feed(chunk)?;
+    }
+    validator.finish()
+}
+
+#[test]
+fn split_anywhere() {
+    for v in PAYLOADS {
+        let expected = expected(v);
+        let bytes = v.as_bytes();
+
+        assert_eq!(format!("{:?}", feed(&[bytes])), expected, "{}", v);
+        for i in 0..=bytes.len() {
+            let (a, b) = bytes.split_at(i);
+            assert_eq!(format!("{:?}", feed(&[a, b])), expected, "{} at {}", v, i);
+        }
+
+        let chunks: Vec<&[u8]> = bytes.chunks(1).collect();
+        assert_eq!(format!("{:?}", feed(&chunks)), expected, "{} by bytes", v);
+    }
+}
+
+#[test]
+fn progress() {
+    let mut validator = jtp::from_chunks();
+    assert_eq!(validator.feed(b"").unwrap(), Progress::NeedMoreInput);
+    assert_eq!(validator.feed(b"[1, ").unwrap(), Progress::NeedMoreInput);
+    assert_eq!(validator.feed(b"2]").unwrap(), Progress::Complete);
+    assert_eq!(validator.feed(b" \n").unwrap(), Progress::Complete);
+    validator.finish().unwrap();
+
+    // A number is not complete until the next byte or the end of input.
+    let mut validator = jtp::from_chunks();
+    assert_eq!(validator.feed(b"12").unwrap(), Progress::NeedMoreInput);
+    assert_eq!(validator.feed(b"3").unwrap(), Progress::NeedMoreInput);
+    validator.finish().unwrap();
+}
+
+#[test]
+fn fail_fast() {
+    let mut validator = jtp::from_chunks().with_max_array_entries(2);
+    validator.feed(b"[1, 2").unwrap();
+    let err = validator.feed(b", 3,").unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxArrayEntriesExceeded { .. }),
+        "unexpected error: {:?}",
+        err
+    );
+
+    let mut validator = jtp::from_chunks();
+    validator.feed(b"[1]").unwrap();
+    let err = validator.feed(b" [").unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::TrailingData(_)),
+        "unexpected error: {:?}",
+        err
+    );
+}
+
+#[test]
+fn incomplete() {
+    for v in [&b"[1, "[..], b"{\"a\"", b"\"abc", br#""\u00"#, b"tr"] {
+        let mut validator = jtp::from_chunks();
+        assert_eq!(validator.feed(v).unwrap(), Progress::NeedMoreInput);
+        assert!(validator.finish().is_err());
+    }
+}
+
+#[test]
+fn max_payload_bytes() {
+    let mut validator = jtp::from_chunks().with_max_payload_bytes(10);
+    validator.feed(b"[\"12345").unwrap();
+    let err = validator.feed(b"67890\"]").unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxPayloadSizeExceeded { limit: 10, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+
+    // An endless stream of whitespace is not retained.
+    let mut validator = jtp::from_chunks().with_max_payload_bytes(1000);
+    let mut result = Ok(Progress::NeedMoreInput);
+    for _ in 0..100 {
+        result = validator.feed(&[b' '; 64]);
+        if result.is_err() {
+            break;
+        }
+    }
+    assert!(matches!(
+        result,
+        Err(jtp::Error::MaxPayloadSizeExceeded { limit: 1000, .. })
+    ));
+}
+
+#[test]
+fn large_token_in_small_chunks() {
+    let mut payload = b"[\"".to_vec();
+    payload.extend_from_slice(&vec![b'a'; 1 << 20]);
+    payload.extend_from_slice(b"\", 1]");
+
+    let mut validator = jtp::from_chunks().with_max_string_length(1 << 20);
+    for chunk in payload.chunks(7) {
+        validator.feed(chunk).unwrap();
+    }
+    validator.finish().unwrap();
+
+    let mut validator = jtp::from_chunks().with_max_string_length((1 << 20) - 1);
+    let result = payload
+        .chunks(7)
+        .try_for_each(|chunk| validator.feed(chunk).map(|_| ()));
+    let err = result.and_then(|_| validator.finish()).unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxStringLengthExceeded { .. }),
+        "unexpected error: {:?}",
+        err
+    );
+}
+
+#[test]
+fn duplicate_names_across_chunks() {
+    let mut validator = jtp::from_chunks().disallow_duplicate_object_entry_name();
+    validator.feed(b"{\"name\": 1, \"na").unwrap();
+    let err = validator.feed(b"me\": 2}").unwrap_err();
+    match err {
+        jtp::Error::DuplicateObjectEntryName { key, .. } => assert_eq!(key, "name"),
+        _ => panic!("unexpected error: {:?}", err),
+    }
+}
@@ -0,0 +1,137 @@
+#![cfg(any(feature = "tokio", feature = "futures-io"))]
+
+mod utils;
+
+use json_threat_protection as jtp;
+
+const VALID: &str = r#"{"a": [1, -2.5e3, "s\n", true, false, null], "b": {"c": {}}}"#;
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn tokio_reader() {
+    jtp::from_tokio_reader(VALID.as_bytes())
+        .validate_async()
+        .await
+        .unwrap();
+
+    let err = jtp::from_tokio_reader(VALID.as_bytes())
+        .with_max_depth(2)
+        .validate_async()
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxDepthExceeded { .. }),
+        "unexpected error: {:?}",
+        err
+    );
+
+    let err = jtp::from_tokio_reader(&b"[1, 2"[..])
+        .validate_async()
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::InvalidJSON(_)),
+        "unexpected error: {:?}",
+        err
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn tokio_chunks_arrive_later() {
+    use tokio::io::AsyncWriteExt;
+
+    let (reader, mut writer) = tokio::io::duplex(4);
+    let write = tokio::spawn(async move {
+        for chunk in VALID.as_bytes().chunks(3) {
+            writer.write_all(chunk).await.unwrap();
+            tokio::task::yield_now().await;
+        }
+    });
+
+    jtp::from_tokio_reader(reader)
+        .disallow_duplicate_object_entry_name()
+        .validate_async()
+        .await
+        .unwrap();
+    write.await.unwrap();
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn tokio_max_payload_bytes() {
+    let payload = format!("[{}]", "1, ".repeat(100_000) + "1");
+    let err = jtp::from_tokio_reader(payload.as_bytes())
+        .with_max_payload_bytes(1000)
+        .validate_async()
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxPayloadSizeExceeded { limit: 1000, .. }),
+        "unexpected error: {:?}",
+        err
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn future_is_send() {
+    fn assert_send<T: Send>(_: T) {}
+    assert_send(jtp::from_tokio_reader(VALID.as_bytes()).validate_async());
+}
+
+#[cfg(feature = "futures-io")]
+#[test]
+fn futures_io_reader() {
+    futures::executor::block_on(async {
+        jtp::from_async_reader(futures::io::Cursor::new(VALID))
+            .validate_async()
+            .await
+            .unwrap();
+
+        let err = jtp::from_async_reader(futures::io::Cursor::new("[1] 2"))
+            .validate_async()
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, jtp::Error::TrailingData(_)),
+            "unexpected error: {:?}",
+            err
+        );
+    });
+}
+
+#[cfg(feature = "futures-io")]
+#[test]
+fn futures_io_error() {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    struct Broken(usize);
+
+    impl futures::io::AsyncRead for Broken {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            if self.0 == 0 {
+                return Poll::Ready(Err(std::io::Error::other("broken")));
+            }
+            self.0 -= 1;
+            buf[0] = b'[';
+            Poll::Ready(Ok(1))
+        }
+    }
+
+    let err = futures::executor::block_on(jtp::from_async_reader(Broken(3)).validate_async())
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::LexerError(jtp::LexerError::ReadError(jtp::ReadError::IoError(..)))
+        ),
+        "unexpected error: {:?}",
+        err
+    );
+}
@@ -0,0 +1,136 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, read::IoRead};
+use std::cell::Cell;
+use std::rc::Rc;
+
+const PAYLOADS: &[&str] = &[
+    r#"{"a": [1, -2.5e+3, 0.25E-1, 10e5, "s\n\u00e9\ud83d\ude00", true, false, null], "b": {"c": {}}}"#,
+    r#"  [ 0 , 10 ,  "x" , "\"\\\/\b\f\r\t" ]   "#,
+    "1234567890",
+    "-0",
+    "0.5",
+    r#"["\u0041"]"#,
+    r#"{"a": 1"#,
+    r#"[01]"#,
+    r#"[-01]"#,
+    r#"[1.]"#,
+    r#"[1.a]"#,
+    r#"[1e]"#,
+    r#"[1e+]"#,
+    r#"[1ea]"#,
+    r#"["\x"]"#,
+    r#"["\uD800"]"#,
+    r#"["\u00"]"#,
+    r#"["\u00g0"]"#,
+    "[\"a\u{1}b\"]",
+    r#"["abc"#,
+    r#"[tru]"#,
+    r#"[1] 2"#,
+    "",
+];
+
+fn validate<R: std::io::Read>(read: IoRead<R>) -> String {
+    format!(
+        "{:?}",
+        jtp::Validator::new(read)
+            .with_max_string_length(16)
+            .validate()
+    )
+}
+
+#[test]
+fn same_as_slice() {
+    for v in PAYLOADS {
+        let expected = format!(
+            "{:?}",
+            jtp::from_slice(v.as_bytes())
+                .with_max_string_length(16)
+                .validate()
+        );
+        assert_eq!(validate(IoRead::new(v.as_bytes())), expected, "{}", v);
+
+        // the tokens are split across the refills of small buffers
+        for capacity in 1..8 {
+            let read = IoRead::with_capacity(capacity, v.as_bytes());
+            assert_eq!(validate(read), expected, "{} with capacity {}", v, capacity);
+        }
+    }
+}
+
+#[test]
+fn multiple_lines() {
+    let v = "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": \"c\"\n}\n";
+    for capacity in 1..8 {
+        jtp::Validator::new(IoRead::with_capacity(capacity, v.as_bytes()))
+            .validate()
+            .unwrap();
+    }
+
+    let v = "[\n1,\n01]";
+    for capacity in [1, 3, 1024] {
+        let err = jtp::Validator::new(IoRead::with_capacity(capacity, v.as_bytes()))
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            format!("{:?}", err),
+            format!(
+                "{:?}",
+                jtp::from_reader(v.as_bytes()).validate().unwrap_err()
+            )
+        );
+    }
+}
+
+struct CountingReader<'a> {
+    data: &'a [u8],
+    calls: Rc<Cell<usize>>,
+}
+
+impl std::io::Read for CountingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.calls.set(self.calls.get() + 1);
+        self.data.read(buf)
+    }
+}
+
+#[test]
+fn read_in_chunks() {
+    let payload = format!(
+        "[{}1]",
+        r#"{"key": "value", "number": 12345}, "#.repeat(10_000)
+    );
+    let calls = Rc::new(Cell::new(0));
+    let reader = CountingReader {
+        data: payload.as_bytes(),
+        calls: calls.clone(),
+    };
+
+    jtp::from_reader(reader).validate().unwrap();
+    assert!(
+        calls.get() <= payload.len() / 4096 + 2,
+        "{} calls for {} bytes",
+        calls.get(),
+        payload.len()
+    );
+}
+
+#[test]
+fn max_payload_bytes_stops_reading() {
+    let payload = format!("[{}1]", "1, ".repeat(100_000));
+    let calls = Rc::new(Cell::new(0));
+    let reader = CountingReader {
+        data: payload.as_bytes(),
+        calls: calls.clone(),
+    };
+
+    let err = jtp::from_reader(reader)
+        .with_max_payload_bytes(100)
+        .validate()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        jtp::Error::MaxPayloadSizeExceeded { limit: 100, .. }
+    ));
+    assert_eq!(calls.get(), 1);
+}
@@ -0,0 +1,173 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, read::IoRead};
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+
+const PAYLOADS: &[&str] = &[
+    r#"{"a": [1, -2.5e+3, 0.25E-1, "s\né😀", true, false, null], "b": {"c": {}}}"#,
+    r#"  [ 0 , 10 ,  "x" , "\"\\\/\b\f\r\t" ]   "#,
+    "1234567890",
+    r#"{"a": 1"#,
+    r#"[01]"#,
+    r#"[1e+]"#,
+    r#"["\uD800"]"#,
+    r#"["abc"#,
+    r#"[tru]"#,
+    r#"[1] 2"#,
+    r#"["too long string"]"#,
+    "",
+];
+
+/// A reader that replays the scripted chunks and errors,
+/// and returns the end of input once the script is exhausted.
+struct ScriptedReader {
+    script: VecDeque<Result<Vec<u8>, ErrorKind>>,
+}
+
+impl ScriptedReader {
+    fn new(script: Vec<Result<Vec<u8>, ErrorKind>>) -> Self {
+        ScriptedReader {
+            script: script.into(),
+        }
+    }
+
+    /// Splits the payload into chunks of one byte, and inserts the error before each chunk.
+    fn interleaved(payload: &[u8], kind: ErrorKind) -> Self {
+        let mut script = Vec::new();
+        for ch in payload {
+            script.push(Err(kind));
+            script.push(Ok(vec![*ch]));
+        }
+        ScriptedReader::new(script)
+    }
+}
+
+impl std::io::Read for ScriptedReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.script.pop_front() {
+            Some(Ok(mut chunk)) => {
+                let n = chunk.len().min(buf.len());
+                buf[..n].copy_from_slice(&chunk[..n]);
+                if n < chunk.len() {
+                    self.script.push_front(Ok(chunk.split_off(n)));
+                }
+                Ok(n)
+            }
+            Some(Err(kind)) => Err(kind.into()),
+            None => Ok(0),
+        }
+    }
+}
+
+fn expected(payload: &str) -> String {
+    format!(
+        "{:?}",
+        jtp::from_slice(payload.as_bytes())
+            .with_max_string_length(8)
+            .validate()
+    )
+}
+
+/// Validates by `validate_nonblocking` until it is finished,
+/// returns the result and the number of times the reader would block.
+fn validate_nonblocking(reader: ScriptedReader) -> (String, usize) {
+    let mut validator = jtp::from_reader(reader).with_max_string_length(8);
+    let mut blocked = 0;
+    loop {
+        match validator.validate_nonblocking() {
+            Ok(true) => return (format!("{:?}", Ok::<(), jtp::Error>(())), blocked),
+            Ok(false) => blocked += 1,
+            Err(e) => return (format!("{:?}", Err::<(), jtp::Error>(e)), blocked),
+        }
+
+        assert!(blocked <= 1024, "the validation never finishes");
+    }
+}
+
+#[test]
+fn interrupted_reads_are_retried() {
+    for v in PAYLOADS {
+        let reader = ScriptedReader::interleaved(v.as_bytes(), ErrorKind::Interrupted);
+        let actual = format!(
+            "{:?}",
+            jtp::from_reader(reader)
+                .with_max_string_length(8)
+                .validate()
+        );
+        assert_eq!(actual, expected(v), "{}", v);
+    }
+}
+
+#[test]
+fn short_reads() {
+    for v in PAYLOADS {
+        let script = v.bytes().map(|ch| Ok(vec![ch])).collect();
+        let actual = format!(
+            "{:?}",
+            jtp::Validator::new(IoRead::with_capacity(4, ScriptedReader::new(script)))
+                .with_max_string_length(8)
+                .validate()
+        );
+        assert_eq!(actual, expected(v), "{}", v);
+    }
+}
+
+#[test]
+fn would_block_is_resumable() {
+    for v in PAYLOADS {
+        for i in 0..=v.len() {
+            let script = vec![
+                Ok(v.as_bytes()[..i].to_vec()),
+                Err(ErrorKind::WouldBlock),
+                Ok(v.as_bytes()[i..].to_vec()),
+            ];
+            let (actual, _) = validate_nonblocking(ScriptedReader::new(script));
+            assert_eq!(actual, expected(v), "{} split at {}", v, i);
+        }
+
+        let (actual, blocked) = validate_nonblocking(ScriptedReader::interleaved(
+            v.as_bytes(),
+            ErrorKind::WouldBlock,
+        ));
+        assert_eq!(actual, expected(v), "{}", v);
+        assert!(blocked > 0 || v.is_empty(), "{}", v);
+    }
+}
+
+#[test]
+fn would_block_after_complete_value() {
+    let script = vec![Ok(b"[1]".to_vec()), Err(ErrorKind::WouldBlock)];
+    let mut validator = jtp::from_reader(ScriptedReader::new(script));
+    assert!(!validator.validate_nonblocking().unwrap());
+    assert!(validator.validate_nonblocking().unwrap());
+
+    let script = vec![
+        Ok(b"[1]".to_vec()),
+        Err(ErrorKind::WouldBlock),
+        Ok(b" 2".to_vec()),
+    ];
+    let mut validator = jtp::from_reader(ScriptedReader::new(script));
+    assert!(!validator.validate_nonblocking().unwrap());
+    assert!(matches!(
+        validator.validate_nonblocking(),
+        Err(jtp::Error::TrailingData(_))
+    ));
+}
+
+#[test]
+fn would_block_in_validate() {
+    let script = vec![Ok(b"[1, ".to_vec()), Err(ErrorKind::WouldBlock)];
+    let err = jtp::from_reader(ScriptedReader::new(script))
+        .validate()
+        .unwrap_err();
+    assert!(format!("{:?}", err).contains("NeedMoreInput"), "{:?}", err);
+}
+
+#[test]
+fn other_errors_are_fatal() {
+    let script = vec![Ok(b"[1, ".to_vec()), Err(ErrorKind::ConnectionReset)];
+    let mut validator = jtp::from_reader(ScriptedReader::new(script));
+    let err = validator.validate_nonblocking().unwrap_err();
+    assert!(format!("{:?}", err).contains("IoError"), "{:?}", err);
+}
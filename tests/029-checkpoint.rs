@@ -0,0 +1,254 @@
+mod utils;
+
+use json_threat_protection::{self as jtp, read::IoRead, read::SliceRead, Checkpoint};
+
+const PAYLOADS: &[&str] = &[
+    r#"{"a": [1, -2.5e+3, "s\né", true, null], "b": {"c": {"d": []}}, "e": "f"}"#,
+    "[\n  [1, 2],\n  [3, [4, [5]]],\n  {\"x\": \"y\"}\n]",
+    r#"[1, 2, 3, 4, 5, 6]"#,
+    r#"{"a": {"b": {"c": {"d": 1}}}}"#,
+    r#"{"a": ["this string is too long"]}"#,
+    r#"{"a": [1, 2, 01]}"#,
+    r#"{"a": [1, 2, 3], "b": 4"#,
+    r#"[1, 2] 3"#,
+];
+
+fn config<R: jtp::read::Read>(validator: jtp::Validator<R>) -> jtp::Validator<R> {
+    validator
+        .with_max_depth(4)
+        .with_max_array_entries(5)
+        .with_max_string_length(16)
+        .with_max_payload_bytes(1024)
+        .disallow_duplicate_object_entry_name()
+}
+
+/// Validates the payload in `steps`, and returns the checkpoint
+/// or `None` if the validation is finished before that.
+fn checkpoint_after(payload: &[u8], steps: usize) -> Option<Checkpoint> {
+    let mut validator = config(jtp::from_slice(payload));
+    match validator.validate_with_steps(steps) {
+        Ok(false) => Some(validator.checkpoint()),
+        _ => None,
+    }
+}
+
+#[test]
+fn same_as_without_checkpoint() {
+    for v in PAYLOADS {
+        let expected = format!("{:?}", config(jtp::from_str(v)).validate());
+
+        for steps in 1..64 {
+            let checkpoint = match checkpoint_after(v.as_bytes(), steps) {
+                Some(checkpoint) => checkpoint,
+                None => break,
+            };
+            let rest = &v.as_bytes()[checkpoint.position().offset..];
+
+            let actual = format!(
+                "{:?}",
+                jtp::Validator::from_checkpoint(SliceRead::new(rest), checkpoint.clone())
+                    .unwrap()
+                    .validate()
+            );
+            assert_eq!(actual, expected, "{} after {} steps", v, steps);
+
+            let actual = format!(
+                "{:?}",
+                jtp::Validator::from_checkpoint(IoRead::new(rest), checkpoint.clone())
+                    .unwrap()
+                    .validate()
+            );
+            assert_eq!(actual, expected, "{} after {} steps", v, steps);
+
+            let mut validator =
+                jtp::Validator::from_checkpoint(jtp::read::FeedRead::new(), checkpoint).unwrap();
+            let actual = format!(
+                "{:?}",
+                rest.chunks(3)
+                    .try_for_each(|chunk| validator.feed(chunk).map(|_| ()))
+                    .and_then(|_| validator.finish())
+            );
+            assert_eq!(actual, expected, "{} after {} steps", v, steps);
+        }
+    }
+}
+
+#[test]
+fn checkpoint_of_checkpoint() {
+    let v = PAYLOADS[0].as_bytes();
+    let mut checkpoint = checkpoint_after(v, 3).unwrap();
+    loop {
+        let offset = checkpoint.position().offset;
+        let mut validator =
+            jtp::Validator::from_checkpoint(SliceRead::new(&v[offset..]), checkpoint).unwrap();
+        if validator.validate_with_steps(3).unwrap() {
+            break;
+        }
+        checkpoint = validator.checkpoint();
+        assert!(checkpoint.position().offset > offset);
+    }
+}
+
+#[test]
+fn state_is_restored() {
+    let v = br#"{"a": {"b": [1, 2, 3, 4, 5, 6]}}"#;
+    let checkpoint = checkpoint_after(v, 9).unwrap();
+    assert_eq!(checkpoint.depth(), 3);
+
+    let offset = checkpoint.position().offset;
+    let err = jtp::Validator::from_checkpoint(SliceRead::new(&v[offset..]), checkpoint)
+        .unwrap()
+        .validate()
+        .unwrap_err();
+    match err {
+        jtp::Error::MaxArrayEntriesExceeded {
+            position,
+            path,
+            limit,
+        } => {
+            assert_eq!(position.offset, 29);
+            assert_eq!(path, "/a/b/5");
+            assert_eq!(limit, 5);
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn duplicate_object_entry_name_across_checkpoint() {
+    let v = br#"{"a": 1, "b": 2, "a": 3}"#;
+    let checkpoint = checkpoint_after(v, 6).unwrap();
+    let offset = checkpoint.position().offset;
+    assert!(offset < 17, "{}", offset);
+
+    let err = jtp::Validator::from_checkpoint(SliceRead::new(&v[offset..]), checkpoint)
+        .unwrap()
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::DuplicateObjectEntryName { ref key, .. } if key == "a"),
+        "{:?}",
+        err
+    );
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn kept_spellings_are_restored() {
+    let v = br#"{"a": 1, "b": 2, "A": 3}"#;
+    let mut validator = jtp::from_slice(v)
+        .disallow_duplicate_object_entry_name()
+        .with_key_normalization(jtp::KeyNormalization {
+            form: None,
+            case_fold: true,
+        });
+    assert!(!validator.validate_with_steps(6).unwrap());
+    let checkpoint = validator.checkpoint();
+
+    let offset = checkpoint.position().offset;
+    let err = jtp::Validator::from_checkpoint(SliceRead::new(&v[offset..]), checkpoint)
+        .unwrap()
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            jtp::Error::DuplicateObjectEntryName { ref key, ref earlier_key, .. }
+                if key == "A" && earlier_key.as_deref() == Some("a")
+        ),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn max_payload_bytes_includes_bytes_before_checkpoint() {
+    let v = br#"[1, 2, 3, 4, 5, 6, 7, 8, 9]"#;
+    let mut validator = jtp::from_slice(v).with_max_payload_bytes(20);
+    assert!(!validator.validate_with_steps(4).unwrap());
+    let checkpoint = validator.checkpoint();
+
+    let offset = checkpoint.position().offset;
+    let err = jtp::Validator::from_checkpoint(SliceRead::new(&v[offset..]), checkpoint)
+        .unwrap()
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, jtp::Error::MaxPayloadSizeExceeded { limit: 20, .. }),
+        "{:?}",
+        err
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_checkpoint() {
+    let v = PAYLOADS[0].as_bytes();
+    let checkpoint = checkpoint_after(v, 10).unwrap();
+
+    let json = serde_json::to_string(&checkpoint).unwrap();
+    let restored: Checkpoint = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, checkpoint);
+
+    let offset = restored.position().offset;
+    jtp::Validator::from_checkpoint(SliceRead::new(&v[offset..]), restored)
+        .unwrap()
+        .validate()
+        .unwrap();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn corrupted_checkpoint() {
+    let v = br#"{"a": {"b": [1, 2, {"c": 3}]}}"#;
+    let checkpoint = checkpoint_after(v, 13).unwrap();
+    assert_eq!(checkpoint.depth(), 4);
+    let json = serde_json::to_value(&checkpoint).unwrap();
+
+    let corruptions: Vec<(&str, serde_json::Value)> = vec![
+        ("depth", serde_json::json!(1)),
+        ("entries", serde_json::json!([1, 1])),
+        ("entries", serde_json::json!([1, 0, 2, 0])),
+        ("names", serde_json::json!(["a", "b", null, null])),
+        ("names", serde_json::json!(["a", "b", "c", null])),
+        ("keys", serde_json::json!([])),
+        (
+            "states",
+            serde_json::json!(["ProcessingObject", "RequireColon"]),
+        ),
+        (
+            "states",
+            serde_json::json!([
+                "ProcessingObject",
+                "ProcessingObject",
+                "ProcessingArray",
+                "ProcessingObject",
+                "OptionalElement"
+            ]),
+        ),
+    ];
+    for (field, value) in corruptions {
+        let mut json = json.clone();
+        json[field] = value.clone();
+        let corrupted: Checkpoint = serde_json::from_value(json).unwrap();
+
+        let offset = corrupted.position().offset;
+        let err = jtp::Validator::from_checkpoint(SliceRead::new(&v[offset..]), corrupted)
+            .err()
+            .unwrap_or_else(|| panic!("{} = {} is accepted", field, value));
+        assert!(
+            matches!(err, jtp::Error::InvalidCheckpoint { .. }),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    // The names could not be read from beyond the position.
+    let mut json = json.clone();
+    json["keys"][0][0][1]["end"] = serde_json::json!(1000);
+    let corrupted: Checkpoint = serde_json::from_value(json).unwrap();
+    assert!(matches!(
+        jtp::Validator::from_checkpoint(SliceRead::new(b""), corrupted),
+        Err(jtp::Error::InvalidCheckpoint { .. })
+    ));
+}